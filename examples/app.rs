@@ -64,6 +64,7 @@ fn parse_args() -> Result<AppArgs, pico_args::Error> {
     };
 
     // It's up to the caller what to do with the remaining arguments.
+    // Use `pargs.finish_strict()?` to report them as an error instead.
     let remaining = pargs.finish();
     if !remaining.is_empty() {
         eprintln!("Warning: unused arguments left: {:?}.", remaining);
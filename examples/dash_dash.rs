@@ -9,26 +9,12 @@ struct Args {
 }
 
 fn parse_args() -> Result<Args, pico_args::Error> {
-    // `from_vec` takes `OsString`, not `String`.
-    let mut args: Vec<_> = std::env::args_os().collect();
-    args.remove(0); // remove the executable path.
-
-    // Find and process `--`.
-    let forwarded_args = if let Some(dash_dash) = args.iter().position(|arg| arg == "--") {
-        // Store all arguments following ...
-        let later_args = args.drain(dash_dash+1..).collect();
-        // .. then remove the `--`
-        args.pop();
-        later_args
-    } else {
-        Vec::new()
-    };
-
-    // Now pass the remaining arguments through to `pico_args`.
-    let mut args = pico_args::Arguments::from_vec(args);
+    let mut args = pico_args::Arguments::from_env();
     let res = Args {
-        forwarded_args,
+        // Flags after `--` are not matched, so `app -- --help` will not print help.
         help: args.contains(["-h", "--help"]),
+        // Takes all arguments following `--`.
+        forwarded_args: args.take_trailing(),
     };
 
     // It's up to the caller what to do with the remaining arguments.
@@ -0,0 +1,51 @@
+const HELP: &str = "\
+App
+
+USAGE:
+  app remote add NAME URL
+  app remote remove NAME
+  app build [--release]
+";
+
+fn main() {
+    let commands = pico_args::Subcommands::new("app")
+        .command("build", build)
+        .nested(pico_args::Subcommands::new("remote")
+            .command("add", remote_add)
+            .command("remove", remote_remove));
+
+    let mut args = pico_args::Arguments::from_env();
+    if args.contains(["-h", "--help"]) {
+        print!("{}", HELP);
+        return;
+    }
+
+    if let Err(e) = commands.dispatch(&mut args) {
+        eprintln!("Error: {}.", e);
+        std::process::exit(1);
+    }
+
+    // It's up to the caller what to do with the remaining arguments.
+    let remaining = args.finish();
+    if !remaining.is_empty() {
+        eprintln!("Warning: unused arguments left: {:?}.", remaining);
+    }
+}
+
+fn build(args: &mut pico_args::Arguments) -> Result<(), pico_args::Error> {
+    println!("build, release: {}", args.contains("--release"));
+    Ok(())
+}
+
+fn remote_add(args: &mut pico_args::Arguments) -> Result<(), pico_args::Error> {
+    let name: String = args.free_from_str()?;
+    let url: String = args.free_from_str()?;
+    println!("remote add {} {}", name, url);
+    Ok(())
+}
+
+fn remote_remove(args: &mut pico_args::Arguments) -> Result<(), pico_args::Error> {
+    let name: String = args.free_from_str()?;
+    println!("remote remove {}", name);
+    Ok(())
+}
@@ -7,14 +7,19 @@ If you think that this library doesn't support some feature, it's probably inten
 - Only flags, options, free arguments and subcommands are supported
 - Options can be separated by a space, `=` or nothing. See build features
 - Arguments can be in any order
+- Flags and options can have any number of aliases, like `["-c", "--colour", "--color"]`
+- Arguments after `--` are always treated as free-standing
 - Non UTF-8 arguments are supported
 
 ## Build features
 
+Build features define the default `Config`, which can be overridden for a specific parser
+via [`Arguments::from_vec_with_config`](struct.Arguments.html#method.from_vec_with_config).
+Since all parsing code is compiled in, disabling them no longer reduces the binary size.
+
 - `eq-separator`
 
-  Allows parsing arguments separated by `=`<br/>
-  This feature adds about 1KiB to the resulting binary
+  Allows parsing arguments separated by `=`
 
 - `short-space-opt`
 
@@ -26,6 +31,9 @@ If you think that this library doesn't support some feature, it's probably inten
 - `combined-flags`
 
   Allows combination of flags, e.g. `-abc` instead of `-a -b -c`<br/>
+  The last flag in a combination can be a short option with a value,
+  which is either the rest of the combination or the next argument,
+  e.g. `-xvf archive.tar` or `-xvfarchive.tar`<br/>
   If `short-space-opt` or `eq-separator` are enabled, you must parse flags after values,
   to prevent ambiguities
 */
@@ -33,8 +41,10 @@ If you think that this library doesn't support some feature, it's probably inten
 #![forbid(unsafe_code)]
 #![warn(missing_docs)]
 
+use std::borrow::Cow;
 use std::ffi::{OsString, OsStr};
 use std::fmt::{self, Display};
+use std::ops::{Bound, RangeBounds};
 use std::str::FromStr;
 
 
@@ -42,402 +52,2050 @@ use std::str::FromStr;
 #[derive(Clone, Debug)]
 pub enum Error {
     /// Arguments must be a valid UTF-8 strings.
-    NonUtf8Argument,
+    ///
+    /// `index` is the position of the argument in the original arguments list
+    /// and `raw` is the argument itself. The same goes for other variants.
+    #[allow(missing_docs)]
+    NonUtf8Argument { index: usize, raw: OsString },
 
     /// A missing free-standing argument.
     MissingArgument,
 
     /// A missing option.
-    MissingOption(Keys),
+    MissingOption(Keys<'static>),
 
     /// An option without a value.
-    OptionWithoutAValue(&'static str),
+    #[allow(missing_docs)]
+    OptionWithoutAValue { key: String, index: usize, raw: OsString },
 
     /// Failed to parse a UTF-8 free-standing argument.
     #[allow(missing_docs)]
-    Utf8ArgumentParsingFailed { value: String, cause: String },
+    Utf8ArgumentParsingFailed { value: String, cause: String, index: usize, raw: OsString },
 
     /// Failed to parse a raw free-standing argument.
     #[allow(missing_docs)]
-    ArgumentParsingFailed { cause: String },
+    ArgumentParsingFailed { cause: String, index: usize, raw: OsString },
+
+    /// A missing subcommand.
+    ///
+    /// `path` is the name of the command followed by the names of its parent subcommands.
+    #[allow(missing_docs)]
+    MissingSubcommand { path: String },
+
+    /// An unknown subcommand.
+    ///
+    /// `path` is the name of the command followed by the names of its parent subcommands.
+    /// Can be empty when the subcommand was parsed via [`Arguments::subcommand_of`].
+    /// `suggestion` is the closest known subcommand, if any.
+    ///
+    /// [`Arguments::subcommand_of`]: struct.Arguments.html#method.subcommand_of
+    #[allow(missing_docs)]
+    UnknownSubcommand {
+        path: String,
+        name: String,
+        suggestion: Option<String>,
+        index: usize,
+        raw: OsString,
+    },
+
+    /// A flag was set more times than allowed.
+    ///
+    /// `index` and `raw` refer to the first occurrence over the limit.
+    #[allow(missing_docs)]
+    TooManyOccurrences { keys: Keys<'static>, max: usize, index: usize, raw: OsString },
+
+    /// An option has fewer values than required.
+    #[allow(missing_docs)]
+    TooFewValues { key: String, min: usize, found: usize, index: usize, raw: OsString },
+
+    /// Failed to parse an item of a delimiter-separated list.
+    ///
+    /// `position` starts from 1.
+    #[allow(missing_docs)]
+    ListItemParsingFailed {
+        value: String,
+        position: usize,
+        cause: String,
+        index: usize,
+        raw: OsString,
+    },
+
+    /// A property is set more than once.
+    ///
+    /// `index` and `raw` refer to the second occurrence.
+    #[allow(missing_docs)]
+    DuplicateProperty { keys: Keys<'static>, name: String, index: usize, raw: OsString },
+
+    /// An option with a fixed number of values has fewer values than required.
+    #[allow(missing_docs)]
+    MissingValues { key: String, expected: usize, missing: usize, index: usize, raw: OsString },
+
+    /// An abbreviated long option matches more than one key.
+    #[allow(missing_docs)]
+    AmbiguousOption { option: String, candidates: Vec<String>, index: usize, raw: OsString },
+
+    /// An option with a single value is set more than once.
+    ///
+    /// `index` and `raw` refer to the second occurrence,
+    /// while `first` and `first_raw` refer to the first one.
+    /// See [`Config::duplicate_policy`].
+    ///
+    /// [`Config::duplicate_policy`]: struct.Config.html#structfield.duplicate_policy
+    #[allow(missing_docs)]
+    DuplicateOption {
+        keys: Keys<'static>,
+        first: usize,
+        first_raw: OsString,
+        index: usize,
+        raw: OsString,
+    },
+
+    /// Arguments were left after parsing.
+    ///
+    /// `options` contains unknown flags and options, while `free` contains
+    /// unexpected free-standing arguments. Each argument is stored along with
+    /// its position in the original arguments list.
+    /// Unknown options also have the closest queried key, if any.
+    #[allow(missing_docs)]
+    UnusedArguments {
+        options: Vec<(usize, OsString, Option<String>)>,
+        free: Vec<(usize, OsString)>,
+    },
 }
 
 impl Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            Error::NonUtf8Argument => {
-                write!(f, "argument is not a UTF-8 string")
+            Error::NonUtf8Argument { index, raw } => {
+                write_argument(f, *index, raw)?;
+                write!(f, "not a UTF-8 string")
             }
             Error::MissingArgument => {
                 write!(f, "free-standing argument is missing")
             }
-            Error::MissingOption(key) => {
-                if key.second().is_empty() {
-                    write!(f, "the '{}' option must be set", key.first())
-                } else {
-                    write!(f, "the '{}/{}' option must be set", key.first(), key.second())
-                }
+            Error::MissingOption(keys) => {
+                write!(f, "the '{}' option must be set", keys)
             }
-            Error::OptionWithoutAValue(key) => {
+            Error::OptionWithoutAValue { key, index, raw } => {
+                write_argument(f, *index, raw)?;
                 write!(f, "the '{}' option doesn't have an associated value", key)
             }
-            Error::Utf8ArgumentParsingFailed { value, cause } => {
+            Error::Utf8ArgumentParsingFailed { value, cause, index, raw } => {
+                write_argument(f, *index, raw)?;
                 write!(f, "failed to parse '{}': {}", value, cause)
             }
-            Error::ArgumentParsingFailed { cause } => {
+            Error::ArgumentParsingFailed { cause, index, raw } => {
+                write_argument(f, *index, raw)?;
                 write!(f, "failed to parse a binary argument: {}", cause)
             }
+            Error::MissingSubcommand { path } => {
+                write!(f, "{}: subcommand is missing", path)
+            }
+            Error::UnknownSubcommand { path, suggestion, index, raw, .. } => {
+                if !path.is_empty() {
+                    write!(f, "{}: ", path)?;
+                }
+
+                write_argument(f, *index, raw)?;
+                write!(f, "unknown subcommand")?;
+                if let Some(suggestion) = suggestion {
+                    write!(f, ", did you mean '{}'?", suggestion)?;
+                }
+
+                Ok(())
+            }
+            Error::TooManyOccurrences { keys, max, index, raw } => {
+                write_argument(f, *index, raw)?;
+                write!(f, "the '{}' flag can be set at most {} time(s)", keys, max)
+            }
+            Error::TooFewValues { key, min, found, index, raw } => {
+                write_argument(f, *index, raw)?;
+                write!(f, "the '{}' option requires at least {} value(s), but {} provided",
+                       key, min, found)
+            }
+            Error::ListItemParsingFailed { value, position, cause, index, raw } => {
+                write_argument(f, *index, raw)?;
+                write!(f, "failed to parse list item {} '{}': {}", position, value, cause)
+            }
+            Error::DuplicateProperty { keys, name, index, raw } => {
+                write_argument(f, *index, raw)?;
+                write!(f, "the '{}' property is set more than once via '{}'", name, keys)
+            }
+            Error::MissingValues { key, expected, missing, index, raw } => {
+                write_argument(f, *index, raw)?;
+                write!(f, "the '{}' option requires {} values, {} missing",
+                       key, expected, missing)
+            }
+            Error::AmbiguousOption { option, candidates, index, raw } => {
+                write_argument(f, *index, raw)?;
+                write!(f, "the '{}' option is ambiguous, could be: {}",
+                       option, candidates.join(", "))
+            }
+            Error::DuplicateOption { keys, first, first_raw, index, raw } => {
+                write_argument(f, *index, raw)?;
+                write!(f, "the '{}' option is already set by argument {} '{}'",
+                       keys, first, first_raw.to_string_lossy())
+            }
+            Error::UnusedArguments { options, free } => {
+                if !options.is_empty() {
+                    write!(f, "unknown options: ")?;
+                    for (i, (index, raw, suggestion)) in options.iter().enumerate() {
+                        if i != 0 {
+                            write!(f, ", ")?;
+                        }
+
+                        write!(f, "'{}' (argument {}", raw.to_string_lossy(), index)?;
+                        if let Some(suggestion) = suggestion {
+                            write!(f, ", did you mean '{}'?", suggestion)?;
+                        }
+                        write!(f, ")")?;
+                    }
+                }
+
+                if !options.is_empty() && !free.is_empty() {
+                    write!(f, "; ")?;
+                }
+
+                if !free.is_empty() {
+                    write!(f, "unexpected arguments: ")?;
+                    for (i, (index, raw)) in free.iter().enumerate() {
+                        if i != 0 {
+                            write!(f, ", ")?;
+                        }
+
+                        write!(f, "'{}' (argument {})", raw.to_string_lossy(), index)?;
+                    }
+                }
+
+                Ok(())
+            }
         }
     }
 }
 
 impl std::error::Error for Error {}
 
-
-#[derive(Clone, Copy, PartialEq)]
-enum PairKind {
-    #[cfg(any(feature = "eq-separator", feature = "short-space-opt"))]
-    SingleArgument,
-    TwoArguments,
+fn write_argument(f: &mut fmt::Formatter, index: usize, raw: &OsStr) -> fmt::Result {
+    write!(f, "argument {} '{}': ", index, raw.to_string_lossy())
 }
 
 
-/// An arguments parser.
-#[derive(Clone, Debug)]
-pub struct Arguments(Vec<OsString>);
+/// A policy for values that are set more than once.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum DuplicatePolicy {
+    /// Return an error.
+    Error,
+    /// Use the first value and ignore the rest.
+    FirstWins,
+    /// Use the last value.
+    LastWins,
+}
 
-impl Arguments {
-    /// Creates a parser from a vector of arguments.
-    ///
-    /// The executable path **must** be removed.
-    ///
-    /// This can be used for supporting `--` arguments to forward to another program.
-    /// See `examples/dash_dash.rs` for an example.
-    pub fn from_vec(args: Vec<OsString>) -> Self {
-        Arguments(args)
-    }
 
-    /// Creates a parser from [`env::args_os`].
-    ///
-    /// The executable path will be removed.
+/// A policy for matching keys against arguments.
+///
+/// By default, keys must match exactly.
+#[derive(Clone, Copy, PartialEq, Default, Debug)]
+pub struct KeyMatching {
+    /// Compare ASCII letters case-insensitively, so `--Dry-Run` matches `--dry-run`.
     ///
-    /// [`env::args_os`]: https://doc.rust-lang.org/stable/std/env/fn.args_os.html
-    pub fn from_env() -> Self {
-        let mut args: Vec<_> = std::env::args_os().collect();
-        args.remove(0);
-        Arguments(args)
-    }
+    /// Note that this applies to short keys as well, so `-v` and `-V` become the same key.
+    pub ignore_ascii_case: bool,
 
-    /// Parses the name of the subcommand, that is, the first positional argument.
-    ///
-    /// Returns `None` when subcommand starts with `-` or when there are no arguments left.
+    /// Treat `_` and `-` as the same character, so `--dry_run` matches `--dry-run`.
     ///
-    /// # Errors
-    ///
-    /// - When arguments is not a UTF-8 string.
-    pub fn subcommand(&mut self) -> Result<Option<String>, Error> {
-        if self.0.is_empty() {
-            return Ok(None);
-        }
+    /// Leading dashes of a key must always match exactly.
+    pub underscore_as_dash: bool,
+}
 
-        if let Some(s) = self.0[0].to_str() {
-            if s.starts_with('-') {
-                return Ok(None);
-            }
+impl KeyMatching {
+    #[inline]
+    fn byte_eq(self, a: u8, b: u8) -> bool {
+        a == b
+            || (self.ignore_ascii_case && a.eq_ignore_ascii_case(&b))
+            || (self.underscore_as_dash && (a == b'_' || a == b'-') && (b == b'_' || b == b'-'))
+    }
+
+    // Checks that `text` starts with `key`.
+    //
+    // Since only ASCII characters can be matched loosely, the matched part of `text`
+    // always has the same length as `key`.
+    #[inline(never)]
+    fn starts_with(self, text: &str, key: &str) -> bool {
+        if text.len() < key.len() {
+            return false;
         }
 
-        self.0.remove(0)
-            .into_string()
-            .map_err(|_| Error::NonUtf8Argument)
-            .map(Some)
+        let dashes = key.len() - key.trim_start_matches('-').len();
+        let text = text.as_bytes();
+        key.bytes().enumerate().all(|(i, c)| {
+            if i < dashes { text[i] == c } else { self.byte_eq(text[i], c) }
+        })
     }
 
-    /// Checks that arguments contain a specified flag.
-    ///
-    /// Searches through all arguments, not only the first/next one.
-    ///
-    /// Calling this method "consumes" the flag: if a flag is present `n`
-    /// times then the first `n` calls to `contains` for that flag will
-    /// return `true`, and subsequent calls will return `false`.
-    ///
-    /// When the "combined-flags" feature is used, repeated letters count
-    /// as repeated flags: `-vvv` is treated the same as `-v -v -v`.
-    pub fn contains<A: Into<Keys>>(&mut self, keys: A) -> bool {
-        self.contains_impl(keys.into())
+    #[inline]
+    fn eq(self, text: &str, key: &str) -> bool {
+        text.len() == key.len() && self.starts_with(text, key)
     }
 
-    #[inline(never)]
-    fn contains_impl(&mut self, keys: Keys) -> bool {
-        if let Some((idx, _)) = self.index_of(keys) {
-            self.0.remove(idx);
-            true
-        } else {
-            #[cfg(feature = "combined-flags")]
-            // Combined flags only work if the short flag is a single character
-            {
-                if keys.first().len() == 2 {
-                    let short_flag = &keys.first()[1..2];
-                    for (n, item) in self.0.iter().enumerate() {
-                        if let Some(s) = item.to_str() {
-                            if s.starts_with('-') && !s.starts_with("--") && s.contains(short_flag) {
-                                if s.len() == 2 {
-                                    // last flag
-                                    self.0.remove(n);
-                                } else {
-                                    self.0[n] = s.replacen(short_flag, "", 1).into();
-                                }
-                                return true;
-                            }
-                        }
-                    }
-                }
-            }
-            false
-        }
+    // Returns the position of an ASCII character inside a flags combination.
+    #[inline]
+    fn find_byte(self, text: &str, c: u8) -> Option<usize> {
+        text.bytes().position(|b| self.byte_eq(b, c))
     }
+}
 
-    /// Parses a key-value pair using `FromStr` trait.
-    ///
-    /// This is a shorthand for `value_from_fn("--key", FromStr::from_str)`
-    pub fn value_from_str<A, T>(&mut self, keys: A) -> Result<T, Error>
-    where
-        A: Into<Keys>,
-        T: FromStr,
-        <T as FromStr>::Err: Display,
-    {
-        self.value_from_fn(keys, FromStr::from_str)
-    }
 
-    /// Parses a key-value pair using a specified function.
+/// A parser configuration.
+///
+/// The defaults of the first three fields are defined by build features,
+/// so they can be overridden for a specific parser.
+/// The rest are disabled by default.
+///
+/// # Example
+///
+/// ```
+/// use pico_args::{Arguments, Config, Syntax};
+///
+/// let config = Config { syntax: Syntax::Go, posix_mode: true, ..Config::default() };
+/// let args = Arguments::from_vec_with_config(vec!["-name=a".into()], config);
+/// ```
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Config {
+    /// Allows parsing arguments separated by `=`.
     ///
-    /// Searches through all argument, not only the first/next one.
+    /// Defaults to the `eq-separator` build feature.
+    pub eq_separator: bool,
+
+    /// Makes the space between short keys and their values optional (e.g. `-w10`).
     ///
-    /// When a key-value pair is separated by a space, the algorithm
-    /// will threat the next argument after the key as a value,
-    /// even if it has a `-/--` prefix.
-    /// So a key-value pair like `--key --value` is not an error.
+    /// Defaults to the `short-space-opt` build feature.
+    pub short_space_opt: bool,
+
+    /// Allows combination of flags, e.g. `-abc` instead of `-a -b -c`.
     ///
-    /// Must be used only once for each option.
+    /// Defaults to the `combined-flags` build feature.
+    pub combined_flags: bool,
+
+    /// Enables the POSIX parsing mode.
     ///
-    /// # Errors
+    /// By default, flags and options are searched through all arguments,
+    /// so they can be placed in any order.
+    /// In the POSIX mode, the search stops at the first free-standing argument,
+    /// that is, the first argument that doesn't start with `-` or is `-` itself.
+    /// This argument and everything after it is preserved as is and can be retrieved
+    /// via `free_from_*` methods or [`Arguments::finish`]. Like with `POSIXLY_CORRECT`,
+    /// `app -v run ls -la` will not match `-l` as our own flag.
     ///
-    /// - When option is not present.
-    /// - When key or value is not a UTF-8 string. Use [`value_from_os_str`] instead.
-    /// - When value parsing failed.
-    /// - When key-value pair is separated not by space or `=`.
+    /// Since we don't know which options have values, the value of an option
+    /// is also treated as the first free-standing argument.
+    /// So options with values must be parsed before flags.
+    /// Otherwise, a flag after `--key value` will not be found.
     ///
-    /// [`value_from_os_str`]: struct.Arguments.html#method.value_from_os_str
-    pub fn value_from_fn<A: Into<Keys>, T, E: Display>(
-        &mut self,
-        keys: A,
-        f: fn(&str) -> Result<T, E>,
-    ) -> Result<T, Error> {
-        let keys = keys.into();
-        match self.opt_value_from_fn(keys, f) {
-            Ok(Some(v)) => Ok(v),
-            Ok(None) => Err(Error::MissingOption(keys)),
-            Err(e) => Err(e),
-        }
-    }
+    /// [`Arguments::finish`]: struct.Arguments.html#method.finish
+    pub posix_mode: bool,
 
-    /// Parses an optional key-value pair using `FromStr` trait.
+    /// How keys are matched against arguments.
     ///
-    /// This is a shorthand for `opt_value_from_fn("--key", FromStr::from_str)`
-    pub fn opt_value_from_str<A, T>(&mut self, keys: A) -> Result<Option<T>, Error>
-    where
-        A: Into<Keys>,
-        T: FromStr,
-        <T as FromStr>::Err: Display,
-    {
-        self.opt_value_from_fn(keys, FromStr::from_str)
-    }
+    /// Applies to all flags and options, including `--key=value` pairs,
+    /// combined flags and global flags and options.
+    pub key_matching: KeyMatching,
 
-    /// Parses an optional key-value pair using a specified function.
+    /// The options syntax.
+    pub syntax: Syntax,
+
+    /// Treats `+` prefixed arguments, like `+x`, as flags.
     ///
-    /// The same as [`value_from_fn`], but returns `Ok(None)` when option is not present.
+    /// Such flags can be parsed via [`Arguments::opt_polarity_flag`] regardless of this setting,
+    /// but by default they are treated as free-standing arguments by [`Arguments::subcommand`],
+    /// [`Arguments::split_subcommand`], the POSIX mode and options with multiple values.
     ///
-    /// [`value_from_fn`]: struct.Arguments.html#method.value_from_fn
-    pub fn opt_value_from_fn<A: Into<Keys>, T, E: Display>(
-        &mut self,
-        keys: A,
-        f: fn(&str) -> Result<T, E>,
-    ) -> Result<Option<T>, Error> {
-        self.opt_value_from_fn_impl(keys.into(), f)
-    }
+    /// [`Arguments::opt_polarity_flag`]: struct.Arguments.html#method.opt_polarity_flag
+    /// [`Arguments::subcommand`]: struct.Arguments.html#method.subcommand
+    /// [`Arguments::split_subcommand`]: struct.Arguments.html#method.split_subcommand
+    pub plus_flags: bool,
 
-    #[inline(never)]
-    fn opt_value_from_fn_impl<T, E: Display>(
-        &mut self,
-        keys: Keys,
-        f: fn(&str) -> Result<T, E>,
-    ) -> Result<Option<T>, Error> {
-        match self.find_value(keys)? {
-            Some((value, kind, idx)) => {
-                match f(value) {
-                    Ok(value) => {
-                        // Remove only when all checks are passed.
-                        self.0.remove(idx);
-                        if kind == PairKind::TwoArguments {
-                            self.0.remove(idx);
-                        }
+    /// A policy for options with a single value that are set more than once.
+    ///
+    /// Applies to [`Arguments::value_from_fn`], [`Arguments::value_from_os_str`]
+    /// and their variants.
+    /// All occurrences of an option are consumed at once, regardless of which key was used,
+    /// and the value is selected according to the policy.
+    /// `DuplicatePolicy::LastWins` allows overriding options set via shell aliases,
+    /// while `DuplicatePolicy::Error` reports positions of the first two occurrences.
+    ///
+    /// By default, is `None` and each call will take the next occurrence.
+    ///
+    /// [`Arguments::value_from_fn`]: struct.Arguments.html#method.value_from_fn
+    /// [`Arguments::value_from_os_str`]: struct.Arguments.html#method.value_from_os_str
+    pub duplicate_policy: Option<DuplicatePolicy>,
+}
 
-                        Ok(Some(value))
-                    }
-                    Err(e) => {
-                        Err(Error::Utf8ArgumentParsingFailed {
-                            value: value.to_string(),
-                            cause: error_to_string(e),
-                        })
-                    }
-                }
-            }
-            None => Ok(None),
+impl Default for Config {
+    #[inline]
+    fn default() -> Self {
+        Config {
+            eq_separator: cfg!(feature = "eq-separator"),
+            short_space_opt: cfg!(feature = "short-space-opt"),
+            combined_flags: cfg!(feature = "combined-flags"),
+            posix_mode: false,
+            key_matching: KeyMatching::default(),
+            syntax: Syntax::default(),
+            plus_flags: false,
+            duplicate_policy: None,
         }
     }
+}
 
-    // The whole logic must be type-independent to prevent monomorphization.
-    #[cfg(any(feature = "eq-separator", feature = "short-space-opt"))]
-    #[inline(never)]
-    fn find_value(
-        &mut self,
-        keys: Keys,
-    ) -> Result<Option<(&str, PairKind, usize)>, Error> {
-        if let Some((idx, key)) = self.index_of(keys) {
-            // Parse a `--key value` pair.
-
-            let value = match self.0.get(idx + 1) {
-                Some(v) => v,
-                None => return Err(Error::OptionWithoutAValue(key)),
-            };
-
-            let value = os_to_str(value)?;
-            Ok(Some((value, PairKind::TwoArguments, idx)))
-        } else if let Some((idx, key)) = self.index_of2(keys) {
-            // Parse a `--key=value` or `-Kvalue` pair.
 
-            let value = &self.0[idx];
+/// An options syntax.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Syntax {
+    /// The default syntax: `-k`, `--key`, `--key value`.
+    ///
+    /// Attached values depend on [`Config::eq_separator`] and [`Config::short_space_opt`].
+    ///
+    /// [`Config::eq_separator`]: struct.Config.html#structfield.eq_separator
+    /// [`Config::short_space_opt`]: struct.Config.html#structfield.short_space_opt
+    Unix,
 
-            // Only UTF-8 strings are supported in this method.
-            let value = value.to_str().ok_or_else(|| Error::NonUtf8Argument)?;
+    /// The syntax of the Go `flag` package.
+    ///
+    /// Long keys can be used with one or two dashes, so `-name`, `--name`,
+    /// `-name value` and `-name=value` are the same option.
+    /// Keys themselves can be declared both as `-name` and `--name`.
+    ///
+    /// The `=` separator is always accepted, while flags cannot be combined
+    /// and values cannot be attached to short keys without `=`, regardless of
+    /// [`Config::eq_separator`], [`Config::combined_flags`] and [`Config::short_space_opt`].
+    /// This way, boolean flags parsed with [`opt_bool_flag`] can have a value only via `=`,
+    /// like `-cache=false`.
+    ///
+    /// [`Config::eq_separator`]: struct.Config.html#structfield.eq_separator
+    /// [`Config::combined_flags`]: struct.Config.html#structfield.combined_flags
+    /// [`Config::short_space_opt`]: struct.Config.html#structfield.short_space_opt
+    /// [`opt_bool_flag`]: struct.Arguments.html#method.opt_bool_flag
+    Go,
 
-            let mut value_range = key.len()..value.len();
+    /// The DOS/Windows syntax in addition to the default one.
+    ///
+    /// Any key can be used with a `/` prefix, so `/?`, `/o` and `/out` match
+    /// `-?`, `-o` and `--out` respectively. Keys can also be declared with a `/` prefix,
+    /// like `/out`, in which case they don't match `-` prefixed arguments.
+    ///
+    /// Values can be separated by `:` as well as by `=`, like `/out:file.txt`.
+    /// Both separators are always accepted, regardless of [`Config::eq_separator`].
+    ///
+    /// Note that arguments starting with `/`, like absolute Unix paths,
+    /// are treated as flags and options in this mode.
+    ///
+    /// [`Config::eq_separator`]: struct.Config.html#structfield.eq_separator
+    Dos,
+}
 
-            if value.as_bytes().get(value_range.start) == Some(&b'=') {
-                #[cfg(feature = "eq-separator")]
-                {
-                    value_range.start += 1;
-                }
-                #[cfg(not(feature = "eq-separator"))]
-                return Err(Error::OptionWithoutAValue(key));
-            } else {
-                // Key must be followed by `=` if not `short-space-opt`
-                #[cfg(not(feature = "short-space-opt"))]
-                return Err(Error::OptionWithoutAValue(key));
-            }
+impl Default for Syntax {
+    #[inline]
+    fn default() -> Self {
+        Syntax::Unix
+    }
+}
 
-            // Check for quoted value.
-            if let Some(c) = value.as_bytes().get(value_range.start).cloned() {
-                if c == b'"' || c == b'\'' {
-                    value_range.start += 1;
 
-                    // A closing quote must be the same as an opening one.
-                    if ends_with(&value[value_range.start..], c) {
-                        value_range.end -= 1;
-                    } else {
-                        return Err(Error::OptionWithoutAValue(key));
-                    }
-                }
-            }
+// Matches keys against arguments according to the syntax and the matching policy.
+#[derive(Clone, Copy)]
+struct Matcher {
+    config: Config,
+}
 
-            // Check length, otherwise String::drain will panic.
-            if value_range.end - value_range.start == 0 {
-                return Err(Error::OptionWithoutAValue(key));
+impl Matcher {
+    // Returns the length of the `text` prefix that matches `key`.
+    #[inline(never)]
+    fn prefix_len(self, text: &str, key: &str) -> Option<usize> {
+        if self.config.syntax == Syntax::Go {
+            // Both `-name` and `--name` are allowed, regardless of how the key is declared.
+            let name = trim_dashes(key);
+            let text_name = trim_dashes(text);
+            let dashes = text.len() - text_name.len();
+            if dashes != 0 && self.config.key_matching.starts_with(text_name, name) {
+                return Some(dashes + name.len());
             }
 
-            // Extract `value` from `--key="value"`.
-            let value = &value[value_range];
+            return None;
+        }
 
-            if value.is_empty() {
-                return Err(Error::OptionWithoutAValue(key));
+        if self.config.syntax == Syntax::Dos && text.starts_with('/') {
+            let name = if key.starts_with('/') { &key[1..] } else { trim_dashes(key) };
+            if self.config.key_matching.starts_with(&text[1..], name) {
+                return Some(1 + name.len());
             }
 
-            Ok(Some((value, PairKind::SingleArgument, idx)))
+            return None;
+        }
+
+        if self.config.key_matching.starts_with(text, key) {
+            Some(key.len())
         } else {
-            Ok(None)
+            None
         }
     }
 
-    // The whole logic must be type-independent to prevent monomorphization.
-    #[cfg(not(any(feature = "eq-separator", feature = "short-space-opt")))]
-    #[inline(never)]
-    fn find_value(
-        &mut self,
-        keys: Keys,
-    ) -> Result<Option<(&str, PairKind, usize)>, Error> {
-        if let Some((idx, key)) = self.index_of(keys) {
-            // Parse a `--key value` pair.
+    #[inline]
+    fn matches(self, text: &OsStr, key: &str) -> bool {
+        match text.to_str() {
+            Some(s) => self.prefix_len(s, key) == Some(s.len()),
+            None => false,
+        }
+    }
 
-            let value = match self.0.get(idx + 1) {
-                Some(v) => v,
-                None => return Err(Error::OptionWithoutAValue(key)),
-            };
+    #[inline]
+    fn eq_separator(self) -> bool {
+        self.config.eq_separator || self.config.syntax != Syntax::Unix
+    }
 
-            let value = os_to_str(value)?;
-            Ok(Some((value, PairKind::TwoArguments, idx)))
-        } else {
-            Ok(None)
+    #[inline]
+    fn is_separator(self, c: u8) -> bool {
+        c == b'=' || (self.config.syntax == Syntax::Dos && c == b':')
+    }
+
+    // Checks that an argument starts with an option prefix, but is not a prefix itself.
+    #[inline(never)]
+    fn is_option_like(self, text: &OsStr) -> bool {
+        let s = text.to_string_lossy();
+        if s.len() < 2 {
+            return false;
         }
+
+        s.starts_with('-')
+            || (self.config.syntax == Syntax::Dos && s.starts_with('/'))
+            || (self.config.plus_flags && s.starts_with('+'))
     }
 
-    /// Parses multiple key-value pairs into the `Vec` using `FromStr` trait.
+    #[inline]
+    fn short_space_opt(self) -> bool {
+        self.config.short_space_opt && self.config.syntax != Syntax::Go
+    }
+
+    #[inline]
+    fn combined_flags(self) -> bool {
+        self.config.combined_flags && self.config.syntax != Syntax::Go
+    }
+}
+
+
+#[derive(Clone, Copy, PartialEq)]
+enum PairKind {
+    SingleArgument,
+    TwoArguments,
+    // A key position inside a flags cluster and whether the value is the next argument.
+    Cluster(usize, bool),
+}
+
+// A key position, the end of its values and the values themselves.
+type MultiValues<'a> = (usize, usize, Vec<&'a str>);
+
+// Positions and kinds of pairs that must be removed once their values are parsed.
+type Pairs = Vec<(usize, PairKind)>;
+
+// An occurrence of an option with a single value.
+struct Occurrence {
+    // The position of the key and the argument itself.
+    key: (usize, OsString),
+    value: OsString,
+    // The position of the argument that contains the value and the argument itself.
+    index: usize,
+    raw: OsString,
+}
+
+
+/// An arguments parser.
+#[derive(Clone, Debug)]
+pub struct Arguments {
+    args: Vec<OsString>,
+    // Original positions of `args`, which are indices in `argv`.
+    indices: Vec<usize>,
+    // The original arguments, so errors can report them as they were passed.
+    argv: Vec<OsString>,
+    // Arguments after the `--` terminator, if any.
+    trailing: Option<Vec<OsString>>,
+    // The original position of the first trailing argument.
+    trailing_index: usize,
+    // Keys that were queried so far.
+    queried: Vec<String>,
+    // Global flags and options. `true` for options with a value.
+    globals: Vec<(Keys<'static>, bool)>,
+    config: Config,
+}
+
+impl Arguments {
+    /// Creates a parser from a vector of arguments.
+    ///
+    /// The executable path **must** be removed.
+    ///
+    /// Everything after the first `--` is treated as free-standing arguments
+    /// and will never be matched as a flag or an option.
+    /// See [`take_trailing`] for forwarding them to another program.
+    ///
+    /// Positions of arguments in errors are indices in `args`.
+    ///
+    /// [`take_trailing`]: struct.Arguments.html#method.take_trailing
+    pub fn from_vec(args: Vec<OsString>) -> Self {
+        Arguments::from_vec_with_config(args, Config::default())
+    }
+
+    /// Creates a parser from a vector of arguments with a specified configuration.
+    ///
+    /// The same as [`from_vec`], but [`Config::eq_separator`], [`Config::short_space_opt`]
+    /// and [`Config::combined_flags`] are taken from `config` instead of build features.
+    ///
+    /// [`from_vec`]: struct.Arguments.html#method.from_vec
+    /// [`Config::eq_separator`]: struct.Config.html#structfield.eq_separator
+    /// [`Config::short_space_opt`]: struct.Config.html#structfield.short_space_opt
+    /// [`Config::combined_flags`]: struct.Config.html#structfield.combined_flags
+    pub fn from_vec_with_config(args: Vec<OsString>, config: Config) -> Self {
+        Arguments::new(args, config, 0)
+    }
+
+    fn new(argv: Vec<OsString>, config: Config, first_index: usize) -> Self {
+        let mut args: Vec<_> = argv.iter().skip(first_index).cloned().collect();
+        let trailing = args.iter().position(|v| v == "--").map(|idx| {
+            let trailing = args.split_off(idx + 1);
+            args.pop(); // remove the `--` itself
+            trailing
+        });
+
+        let indices = (first_index..first_index + args.len()).collect();
+        let trailing_index = first_index + args.len() + 1;
+        Arguments {
+            args,
+            indices,
+            argv,
+            trailing,
+            trailing_index,
+            queried: Vec::new(),
+            globals: Vec::new(),
+            config,
+        }
+    }
+
+    /// Creates a parser from [`env::args_os`].
+    ///
+    /// The executable path will be removed.
+    /// Positions of arguments in errors are indices in `args_os`,
+    /// so the first argument is at 1.
+    ///
+    /// [`env::args_os`]: https://doc.rust-lang.org/stable/std/env/fn.args_os.html
+    pub fn from_env() -> Self {
+        Arguments::from_env_with_config(Config::default())
+    }
+
+    /// Creates a parser from [`env::args_os`] with a specified configuration.
+    ///
+    /// The executable path will be removed.
+    ///
+    /// [`env::args_os`]: https://doc.rust-lang.org/stable/std/env/fn.args_os.html
+    pub fn from_env_with_config(config: Config) -> Self {
+        Arguments::new(std::env::args_os().collect(), config, 1)
+    }
+
+    #[inline]
+    fn matcher(&self) -> Matcher {
+        Matcher { config: self.config }
+    }
+
+    /// Expands abbreviated long options.
+    ///
+    /// Like with GNU `getopt_long`, `--verb` will be treated as `--verbose`
+    /// as long as no other key from `flags` and `options` starts with `--verb`.
+    /// `--verb=value` is expanded as well.
+    ///
+    /// `flags` are long keys without a value, while `options` are long keys with a value.
+    /// An argument that follows an option is its value and is never expanded,
+    /// so `--name --verb` stays as is. Values of options that are not listed,
+    /// like short ones, are not known and will be expanded if they look like an abbreviation.
+    ///
+    /// Since keys are not known beforehand, this method must be called
+    /// before any flags and options are parsed and `flags` and `options` must contain
+    /// all long keys that will be used.
+    ///
+    /// # Errors
+    ///
+    /// - When an abbreviation matches more than one key. In this case, nothing is expanded.
+    pub fn expand_abbreviations(&mut self, flags: &[&str], options: &[&str]) -> Result<(), Error> {
+        let matching = self.config.key_matching;
+        let mut expanded = Vec::new();
+        let mut is_value = false;
+        for (idx, arg) in self.options().iter().enumerate() {
+            if is_value {
+                is_value = false;
+                continue;
+            }
+
+            let arg = match arg.to_str() {
+                Some(s) if s.starts_with("--") && s.len() > 2 => s,
+                _ => continue,
+            };
+
+            let (name, value) = match arg.find('=') {
+                Some(i) => (&arg[..i], &arg[i..]),
+                None => (arg, ""),
+            };
+
+            let keys = flags.iter().chain(options);
+            if let Some(key) = keys.clone().find(|key| matching.eq(name, key)) {
+                is_value = value.is_empty() && options.contains(key);
+                continue;
+            }
+
+            let candidates: Vec<_> = keys
+                .filter(|key| matching.starts_with(key, name))
+                .map(|key| key.to_string())
+                .collect();
+
+            match candidates.len() {
+                0 => {}
+                1 => {
+                    is_value = value.is_empty() && options.contains(&candidates[0].as_str());
+                    expanded.push((idx, format!("{}{}", candidates[0], value)));
+                }
+                _ => {
+                    let (index, raw) = self.origin(idx);
+                    let option = name.to_string();
+                    return Err(Error::AmbiguousOption { option, candidates, index, raw });
+                }
+            }
+        }
+
+        for (idx, arg) in expanded {
+            self.args[idx] = arg.into();
+        }
+
+        Ok(())
+    }
+
+    /// Declares a global flag.
+    ///
+    /// Global flags and options can be placed both before and after a subcommand name,
+    /// like `app --verbose build` and `app build --verbose`.
+    ///
+    /// [`subcommand`] will skip them instead of returning `None`,
+    /// while [`split_subcommand`] will keep them in this parser,
+    /// so they can be parsed at the top level regardless of their position.
+    ///
+    /// This method doesn't parse anything by itself.
+    /// Global flags still have to be queried via [`contains`].
+    ///
+    /// [`subcommand`]: struct.Arguments.html#method.subcommand
+    /// [`split_subcommand`]: struct.Arguments.html#method.split_subcommand
+    /// [`contains`]: struct.Arguments.html#method.contains
+    pub fn add_global_flag<'a, A: Into<Keys<'a>>>(&mut self, keys: A) {
+        self.globals.push((keys.into().to_static(), false));
+    }
+
+    /// Declares a global option with a value.
+    ///
+    /// The same as [`add_global_flag`], but for options like `--config PATH`.
+    ///
+    /// [`add_global_flag`]: struct.Arguments.html#method.add_global_flag
+    pub fn add_global_option<'a, A: Into<Keys<'a>>>(&mut self, keys: A) {
+        self.globals.push((keys.into().to_static(), true));
+    }
+
+    /// Parses the name of the subcommand, that is, the first positional argument.
+    ///
+    /// Global flags and options that precede the subcommand are skipped and left in place.
+    ///
+    /// Returns `None` when subcommand starts with `-` or when there are no arguments left.
+    /// In the DOS syntax, a subcommand starting with `/` is `None` as well.
+    /// The same goes for `+` when [`Config::plus_flags`] is enabled.
+    ///
+    /// # Errors
+    ///
+    /// - When arguments is not a UTF-8 string.
+    ///
+    /// [`Config::plus_flags`]: struct.Config.html#structfield.plus_flags
+    pub fn subcommand(&mut self) -> Result<Option<String>, Error> {
+        let idx = match self.subcommand_index() {
+            Some(idx) => idx,
+            None => return Ok(None),
+        };
+
+        let name = self.arg_str(idx)?.to_string();
+        self.remove_arg(idx);
+        Ok(Some(name))
+    }
+
+    // Returns the position of the subcommand, skipping global flags and options.
+    #[inline(never)]
+    fn subcommand_index(&self) -> Option<usize> {
+        let mut idx = 0;
+        loop {
+            if idx >= self.args.len() {
+                return None;
+            }
+
+            match self.global_len(idx) {
+                0 => break,
+                n => idx += n,
+            }
+        }
+
+        if let Some(s) = self.args[idx].to_str() {
+            if s.starts_with('-') || self.matcher().is_option_like(&self.args[idx]) {
+                return None;
+            }
+        }
+
+        Some(idx)
+    }
+
+    /// Parses the name of the subcommand, which must be one of `names`.
+    ///
+    /// The same as [`subcommand`], but the subcommand must be known.
+    ///
+    /// # Errors
+    ///
+    /// - When the subcommand is not one of `names`.
+    ///   The error will contain the closest known name, if any,
+    ///   and the subcommand will not be removed.
+    /// - When the subcommand is not a UTF-8 string.
+    ///
+    /// [`subcommand`]: struct.Arguments.html#method.subcommand
+    pub fn subcommand_of(&mut self, names: &[&str]) -> Result<Option<String>, Error> {
+        let idx = match self.subcommand_index() {
+            Some(idx) => idx,
+            None => return Ok(None),
+        };
+
+        let name = self.arg_str(idx)?.to_string();
+        if !names.contains(&name.as_str()) {
+            let suggestion = closest(&name, names);
+            let (index, raw) = self.origin(idx);
+            let path = String::new();
+            return Err(Error::UnknownSubcommand { path, name, suggestion, index, raw });
+        }
+
+        // Remove only when all checks are passed.
+        self.remove_arg(idx);
+        Ok(Some(name))
+    }
+
+    /// Splits arguments at the subcommand.
+    ///
+    /// Unlike [`subcommand`], the subcommand doesn't have to be the first argument.
+    /// It's the first argument that doesn't start with `-` or is `-` itself.
+    /// Arguments that precede it stay in this parser, while the arguments that follow it
+    /// are moved to the returned one, along with the arguments after `--`.
+    /// This way, `app --out a build --out b` can be parsed unambiguously
+    /// and each level can be [`finish`]ed independently.
+    ///
+    /// Since we don't know which options have values, options with values
+    /// that precede the subcommand must be parsed before calling this method.
+    /// Otherwise, `a` from the example above will be treated as a subcommand.
+    ///
+    /// Global flags and options that follow the subcommand
+    /// are not moved to the returned parser and should be parsed by this one.
+    ///
+    /// The returned parser inherits settings of this one.
+    ///
+    /// Returns `None` when there are no free-standing arguments left.
+    ///
+    /// # Errors
+    ///
+    /// - When the subcommand is not a UTF-8 string.
+    ///
+    /// [`subcommand`]: struct.Arguments.html#method.subcommand
+    /// [`finish`]: struct.Arguments.html#method.finish
+    pub fn split_subcommand(&mut self) -> Result<Option<(String, Arguments)>, Error> {
+        let idx = self.first_free_index();
+        if idx == self.args.len() {
+            return Ok(None);
+        }
+
+        let name = self.arg_str(idx)?.to_string();
+        let args = self.args.split_off(idx + 1);
+        let indices = self.indices.split_off(idx + 1);
+        self.remove_arg(idx); // remove the subcommand itself
+
+        let mut child = Arguments {
+            args,
+            indices,
+            argv: self.argv.clone(),
+            trailing: self.trailing.take(),
+            trailing_index: self.trailing_index,
+            queried: Vec::new(),
+            globals: self.globals.clone(),
+            config: self.config,
+        };
+
+        // Move global flags and options back to the parent.
+        let mut end = child.options().len();
+        let mut idx = 0;
+        while idx < end {
+            match child.global_len(idx) {
+                0 => idx += 1,
+                n => {
+                    let n = std::cmp::min(n, end - idx);
+                    self.args.extend(child.args.drain(idx..idx + n));
+                    self.indices.extend(child.indices.drain(idx..idx + n));
+                    end -= n;
+                }
+            }
+        }
+
+        Ok(Some((name, child)))
+    }
+
+    /// Checks that arguments contain a specified flag.
+    ///
+    /// Searches through all arguments, not only the first/next one.
+    ///
+    /// Calling this method "consumes" the flag: if a flag is present `n`
+    /// times then the first `n` calls to `contains` for that flag will
+    /// return `true`, and subsequent calls will return `false`.
+    ///
+    /// When [`Config::combined_flags`] is enabled, repeated letters count
+    /// as repeated flags: `-vvv` is treated the same as `-v -v -v`.
+    ///
+    /// [`Config::combined_flags`]: struct.Config.html#structfield.combined_flags
+    pub fn contains<'a, A: Into<Keys<'a>>>(&mut self, keys: A) -> bool {
+        self.contains_impl(&keys.into())
+    }
+
+    #[inline(never)]
+    fn contains_impl(&mut self, keys: &Keys) -> bool {
+        self.remember(keys);
+        if let Some((idx, _)) = self.index_of(keys) {
+            self.remove_arg(idx);
+            true
+        } else {
+            if self.matcher().combined_flags() {
+                for (n, item) in self.options().iter().enumerate() {
+                    if let Some(s) = item.to_str() {
+                        if !s.starts_with('-') || s.starts_with("--") {
+                            continue;
+                        }
+
+                        if let Some(pos) = self.find_short_flag(&s[1..], keys) {
+                            if s.len() == 2 {
+                                // last flag
+                                self.remove_arg(n);
+                            } else {
+                                let mut flags = s.to_string();
+                                flags.remove(pos + 1);
+                                self.args[n] = flags.into();
+                            }
+                            return true;
+                        }
+                    }
+                }
+            }
+            false
+        }
+    }
+
+    /// Counts occurrences of a specified flag.
+    ///
+    /// Unlike [`contains`], consumes all occurrences of the flag at once,
+    /// including both short and long keys.
+    /// When [`Config::combined_flags`] is enabled, all letters in all combinations
+    /// are counted as well, so `-vv --verbose -qv` counts as 4 for `["-v", "--verbose"]`.
+    ///
+    /// [`contains`]: struct.Arguments.html#method.contains
+    /// [`Config::combined_flags`]: struct.Config.html#structfield.combined_flags
+    pub fn count<'a, A: Into<Keys<'a>>>(&mut self, keys: A) -> usize {
+        self.count_impl(&keys.into(), usize::max_value()).0
+    }
+
+    /// Counts occurrences of a specified flag with an upper bound.
+    ///
+    /// The same as [`count`], but returns an error when the flag is set more than `max` times.
+    ///
+    /// [`count`]: struct.Arguments.html#method.count
+    pub fn count_max<'a, A: Into<Keys<'a>>>(&mut self, keys: A, max: usize) -> Result<usize, Error> {
+        let keys = keys.into();
+        let (count, index) = self.count_impl(&keys, max);
+        if count > max {
+            let raw = self.argv[index].clone();
+            Err(Error::TooManyOccurrences { keys: keys.to_static(), max, index, raw })
+        } else {
+            Ok(count)
+        }
+    }
+
+    // Also returns the original position of the first occurrence over `max`,
+    // which is meaningful only when the count exceeds it.
+    #[inline(never)]
+    fn count_impl(&mut self, keys: &Keys, max: usize) -> (usize, usize) {
+        self.remember(keys);
+        self.validate_keys(keys);
+        let mut count = 0;
+        let mut over = 0;
+        let mut end = self.options().len();
+        let mut idx = 0;
+        while idx < end {
+            let index = self.indices[idx];
+            if keys.0.iter().any(|key| !key.is_empty() && self.matcher().matches(&self.args[idx], key)) {
+                self.remove_arg(idx);
+                end -= 1;
+                if count == max {
+                    over = index;
+                }
+                count += 1;
+                continue;
+            }
+
+            let (n, removed) = self.take_combined_flags(idx, keys, '-');
+            if count <= max && n > max - count {
+                over = index;
+            }
+            count += n;
+            if removed {
+                end -= 1;
+                continue;
+            }
+
+            idx += 1;
+        }
+
+        (count, over)
+    }
+
+    /// Parses a boolean flag.
+    ///
+    /// Like [`count`], consumes all occurrences of the flag at once.
+    /// The last occurrence wins, so `--color --no-color` is `false`.
+    ///
+    /// - `-c` and `--color` are `true`
+    /// - `--no-color` is `false`. Only long keys can be negated.
+    /// - `--color=VALUE` is `true` or `false` depending on `VALUE`,
+    ///   which can be `true/false`, `yes/no`, `on/off` or `1/0`.
+    ///   Requires [`Config::eq_separator`].
+    /// - When [`Config::combined_flags`] is enabled, a short key in a combination is `true`.
+    ///
+    /// Returns `Ok(None)` when flag is not present.
+    ///
+    /// # Errors
+    ///
+    /// - When a flag value is not a boolean. In this case, nothing is consumed.
+    ///
+    /// [`count`]: struct.Arguments.html#method.count
+    /// [`Config::eq_separator`]: struct.Config.html#structfield.eq_separator
+    /// [`Config::combined_flags`]: struct.Config.html#structfield.combined_flags
+    pub fn opt_bool_flag<'a, A: Into<Keys<'a>>>(&mut self, keys: A) -> Result<Option<bool>, Error> {
+        self.opt_bool_flag_impl(&keys.into())
+    }
+
+    #[inline(never)]
+    fn opt_bool_flag_impl(&mut self, keys: &Keys) -> Result<Option<bool>, Error> {
+        self.remember(keys);
+        self.validate_keys(keys);
+        let negated: Vec<String> = keys.0.iter()
+            .filter(|key| key.starts_with("--"))
+            .map(|key| format!("--no-{}", &key[2..]))
+            .collect();
+
+        let matcher = self.matcher();
+
+        // Check values first, so nothing will be removed on error.
+        if matcher.eq_separator() {
+            for (idx, item) in self.options().iter().enumerate() {
+                for key in &keys.0 {
+                    if key.is_empty() {
+                        continue;
+                    }
+
+                    if let Some(value) = eq_value(item, key, matcher) {
+                        if parse_bool(value).is_none() {
+                            let cause = "expected true/false, yes/no, on/off or 1/0".to_string();
+                            return Err(self.parsing_failed(idx, value, cause));
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut flag = None;
+        let mut end = self.options().len();
+        let mut idx = 0;
+        while idx < end {
+            let mut value = None;
+            if keys.0.iter().any(|key| !key.is_empty() && self.matcher().matches(&self.args[idx], key)) {
+                value = Some(true);
+            } else if negated.iter().any(|key| self.matcher().matches(&self.args[idx], key)) {
+                value = Some(false);
+            }
+
+            if matcher.eq_separator() {
+                for key in &keys.0 {
+                    if key.is_empty() {
+                        continue;
+                    }
+
+                    if let Some(v) = eq_value(&self.args[idx], key, matcher) {
+                        value = parse_bool(v);
+                    }
+                }
+            }
+
+            if value.is_some() {
+                flag = value;
+                self.remove_arg(idx);
+                end -= 1;
+                continue;
+            }
+
+            let (n, removed) = self.take_combined_flags(idx, keys, '-');
+            if n != 0 {
+                flag = Some(true);
+            }
+
+            if removed {
+                end -= 1;
+                continue;
+            }
+
+            idx += 1;
+        }
+
+        Ok(flag)
+    }
+
+    /// Parses a flag that can be set via `-` and unset via `+`.
+    ///
+    /// Like [`opt_bool_flag`], consumes all occurrences of the flag at once
+    /// and the last occurrence wins, so `-x +x` is `false`.
+    ///
+    /// - `-x` and `--name` are `true`
+    /// - `+x` and `+name` are `false`
+    ///
+    /// Like in X11 tools, e.g. `xterm -sb` and `xterm +sb`, the `+` form of a key
+    /// is a single `+` followed by the key name. This is the same for short and long keys.
+    ///
+    /// When [`Config::combined_flags`] is enabled, a short key in a combination
+    /// has the polarity of the combination, so `+abc` unsets `-b`.
+    /// An argument that is the `+` form of one of the long keys is not treated as a combination,
+    /// so `+verbose` unsets `["-v", "--verbose"]` as a whole. But since other keys are not known,
+    /// `+verbose` would unset `-v`, `-e` and so on, when queried via `-v` alone.
+    /// Therefore, flags with long keys should be queried first.
+    ///
+    /// Returns `None` when flag is not present.
+    ///
+    /// See [`Config::plus_flags`] to prevent `+x` from being treated as a free-standing argument.
+    ///
+    /// [`opt_bool_flag`]: struct.Arguments.html#method.opt_bool_flag
+    /// [`Config::plus_flags`]: struct.Config.html#structfield.plus_flags
+    /// [`Config::combined_flags`]: struct.Config.html#structfield.combined_flags
+    pub fn opt_polarity_flag<'a, A: Into<Keys<'a>>>(&mut self, keys: A) -> Option<bool> {
+        self.opt_polarity_flag_impl(&keys.into())
+    }
+
+    #[inline(never)]
+    fn opt_polarity_flag_impl(&mut self, keys: &Keys) -> Option<bool> {
+        self.remember(keys);
+        self.validate_keys(keys);
+
+        // `-x` becomes `+x` and `--name` becomes `+name`.
+        let plus_keys: Vec<String> = keys.0.iter()
+            .map(|key| format!("+{}", key.trim_start_matches('-')))
+            .collect();
+
+        let matcher = self.matcher();
+        let mut flag = None;
+        let mut end = self.options().len();
+        let mut idx = 0;
+        while idx < end {
+            let mut value = None;
+            for (key, plus_key) in keys.0.iter().zip(&plus_keys) {
+                if key.is_empty() {
+                    continue;
+                }
+
+                if matcher.matches(&self.args[idx], key) {
+                    value = Some(true);
+                } else if self.args[idx].to_str().map_or(false, |s| self.config.key_matching.eq(s, plus_key)) {
+                    value = Some(false);
+                }
+            }
+
+            if value.is_some() {
+                flag = value;
+                self.remove_arg(idx);
+                end -= 1;
+                continue;
+            }
+
+            let polarity = self.args[idx].to_str().map_or(true, |s| !s.starts_with('+'));
+            let prefix = if polarity { '-' } else { '+' };
+            let (n, removed) = self.take_combined_flags(idx, keys, prefix);
+            if n != 0 {
+                flag = Some(polarity);
+            }
+
+            if removed {
+                end -= 1;
+                continue;
+            }
+
+            idx += 1;
+        }
+
+        flag
+    }
+
+    // Removes all occurrences of a short flag from a flags combination at `idx`,
+    // which starts with `prefix`.
+    // Returns the number of removed flags and whether the whole argument was removed.
+    #[inline(never)]
+    fn take_combined_flags(&mut self, idx: usize, keys: &Keys, prefix: char) -> (usize, bool) {
+        if !self.matcher().combined_flags() {
+            return (0, false);
+        }
+
+        if let Some(s) = self.args[idx].to_str() {
+            if s.starts_with(prefix) && !s[1..].starts_with(prefix) {
+                let matching = self.config.key_matching;
+                let is_flag = |c: char| {
+                    c.is_ascii() && keys.short_flags().any(|f| matching.byte_eq(c as u8, f))
+                };
+                let count = s[1..].chars().filter(|c| is_flag(*c)).count();
+                if count == 0 {
+                    return (0, false);
+                }
+
+                let flags: String = s.chars().enumerate()
+                    .filter(|&(i, c)| i == 0 || !is_flag(c))
+                    .map(|(_, c)| c)
+                    .collect();
+                if flags.len() == 1 {
+                    // no flags left
+                    self.remove_arg(idx);
+                    return (count, true);
+                }
+
+                self.args[idx] = flags.into();
+                return (count, false);
+            }
+        }
+
+        (0, false)
+    }
+
+    /// Parses a key-value pair using `FromStr` trait.
+    ///
+    /// This is a shorthand for `value_from_fn("--key", FromStr::from_str)`
+    pub fn value_from_str<'a, A, T>(&mut self, keys: A) -> Result<T, Error>
+    where
+        A: Into<Keys<'a>>,
+        T: FromStr,
+        <T as FromStr>::Err: Display,
+    {
+        self.value_from_fn(keys, FromStr::from_str)
+    }
+
+    /// Parses a key-value pair using a specified function.
+    ///
+    /// Searches through all argument, not only the first/next one.
+    ///
+    /// When a key-value pair is separated by a space, the algorithm
+    /// will threat the next argument after the key as a value,
+    /// even if it has a `-/--` prefix.
+    /// So a key-value pair like `--key --value` is not an error.
+    ///
+    /// Must be used only once for each option, unless a policy is set
+    /// via [`Config::duplicate_policy`].
+    ///
+    /// # Errors
+    ///
+    /// - When option is not present.
+    /// - When key or value is not a UTF-8 string. Use [`value_from_os_str`] instead.
+    /// - When value parsing failed.
+    /// - When key-value pair is separated not by space or `=`.
+    /// - When option is set more than once and the policy is `DuplicatePolicy::Error`.
+    ///
+    /// [`value_from_os_str`]: struct.Arguments.html#method.value_from_os_str
+    /// [`Config::duplicate_policy`]: struct.Config.html#structfield.duplicate_policy
+    pub fn value_from_fn<'a, A: Into<Keys<'a>>, T, E: Display>(
+        &mut self,
+        keys: A,
+        f: fn(&str) -> Result<T, E>,
+    ) -> Result<T, Error> {
+        let keys = keys.into();
+        match self.opt_value_from_fn(&keys, f) {
+            Ok(Some(v)) => Ok(v),
+            Ok(None) => Err(Error::MissingOption(keys.to_static())),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Parses an optional key-value pair using `FromStr` trait.
+    ///
+    /// This is a shorthand for `opt_value_from_fn("--key", FromStr::from_str)`
+    pub fn opt_value_from_str<'a, A, T>(&mut self, keys: A) -> Result<Option<T>, Error>
+    where
+        A: Into<Keys<'a>>,
+        T: FromStr,
+        <T as FromStr>::Err: Display,
+    {
+        self.opt_value_from_fn(keys, FromStr::from_str)
+    }
+
+    /// Parses an optional key-value pair using a specified function.
+    ///
+    /// The same as [`value_from_fn`], but returns `Ok(None)` when option is not present.
+    ///
+    /// [`value_from_fn`]: struct.Arguments.html#method.value_from_fn
+    pub fn opt_value_from_fn<'a, A: Into<Keys<'a>>, T, E: Display>(
+        &mut self,
+        keys: A,
+        f: fn(&str) -> Result<T, E>,
+    ) -> Result<Option<T>, Error> {
+        self.opt_value_from_fn_impl(&keys.into(), f)
+    }
+
+    #[inline(never)]
+    fn opt_value_from_fn_impl<T, E: Display>(
+        &mut self,
+        keys: &Keys,
+        f: fn(&str) -> Result<T, E>,
+    ) -> Result<Option<T>, Error> {
+        self.remember(keys);
+        if let Some(policy) = self.config.duplicate_policy {
+            return match self.select_by_policy(keys, policy, false)? {
+                Some((item, pairs)) => {
+                    // Always a UTF-8 string, since it was found by `find_any_value`.
+                    let value = item.value.to_string_lossy();
+                    match f(&value) {
+                        Ok(value) => {
+                            // Remove only when all checks are passed.
+                            self.remove_pairs(pairs);
+                            Ok(Some(value))
+                        }
+                        Err(e) => Err(Error::Utf8ArgumentParsingFailed {
+                            value: value.to_string(),
+                            cause: error_to_string(e),
+                            index: item.index,
+                            raw: item.raw,
+                        }),
+                    }
+                }
+                None => Ok(None),
+            };
+        }
+
+        self.next_value_from_fn(keys, f)
+    }
+
+    fn next_value_from_fn<T, E: Display>(
+        &mut self,
+        keys: &Keys,
+        f: fn(&str) -> Result<T, E>,
+    ) -> Result<Option<T>, Error> {
+        match self.find_any_value(keys)? {
+            Some((value, kind, idx)) => {
+                match f(value) {
+                    Ok(value) => {
+                        // Remove only when all checks are passed.
+                        self.remove_pair(idx, kind);
+                        Ok(Some(value))
+                    }
+                    Err(e) => {
+                        Err(self.parsing_failed(value_index(idx, kind), value, error_to_string(e)))
+                    }
+                }
+            }
+            None => Ok(None),
+        }
+    }
+
+    #[inline]
+    fn find_any_value(&self, keys: &Keys) -> Result<Option<(&str, PairKind, usize)>, Error> {
+        // Use whichever pair comes first, so a cluster isn't skipped in favor of a later key.
+        let end = self.find_key(keys).map_or(self.args.len(), |(idx, _, _)| idx);
+        match self.find_cluster_value(keys, end)? {
+            Some(v) => Ok(Some(v)),
+            None => self.find_value(keys),
+        }
+    }
+
+    #[inline(never)]
+    fn remove_pair(&mut self, idx: usize, kind: PairKind) {
+        match kind {
+            PairKind::Cluster(pos, next) => {
+                if next {
+                    self.remove_arg(idx + 1);
+                }
+
+                // Keep flags that precede the key.
+                if pos == 1 {
+                    self.remove_arg(idx);
+                } else {
+                    let flags = self.args[idx].to_string_lossy()[..pos].to_string();
+                    self.args[idx] = flags.into();
+                }
+            }
+            PairKind::TwoArguments => {
+                self.remove_arg(idx);
+                self.remove_arg(idx);
+            }
+            PairKind::SingleArgument => {
+                self.remove_arg(idx);
+            }
+        }
+    }
+
+    // The whole logic must be type-independent to prevent monomorphization.
+    #[inline(never)]
+    fn find_value(
+        &self,
+        keys: &Keys,
+    ) -> Result<Option<(&str, PairKind, usize)>, Error> {
+        match self.find_key(keys) {
+            Some((idx, key, true)) => {
+                let value = self.attached_value(idx, key)?;
+                Ok(Some((value, PairKind::SingleArgument, idx)))
+            }
+            Some((idx, key, false)) => {
+                // Parse a `--key value` pair.
+
+                if idx + 1 == self.args.len() {
+                    return Err(self.without_value(idx, key));
+                }
+
+                let value = self.arg_str(idx + 1)?;
+                Ok(Some((value, PairKind::TwoArguments, idx)))
+            }
+            None => Ok(None),
+        }
+    }
+
+    // Returns the position of the first `--key value` or `--key=value` pair
+    // and whether the value is attached.
+    #[inline(never)]
+    fn find_key<'k>(&self, keys: &'k Keys) -> Option<(usize, &'k str, bool)> {
+        // Use whichever pair comes first.
+        match (self.index_of(keys), self.index_of2(keys)) {
+            (Some((i, _)), Some((j, key))) if j < i => Some((j, key, true)),
+            (Some((i, key)), _) => Some((i, key, false)),
+            (None, Some((j, key))) => Some((j, key, true)),
+            (None, None) => None,
+        }
+    }
+
+    // Parses a `--key=value` or `-Kvalue` pair.
+    #[inline(never)]
+    fn attached_value(&self, idx: usize, key: &str) -> Result<&str, Error> {
+        // Only UTF-8 strings are supported in this method.
+        let value = self.arg_str(idx)?;
+
+        let matcher = self.matcher();
+        let key_len = matcher.prefix_len(value, key).unwrap_or(key.len());
+        let mut value_range = key_len..value.len();
+
+        if value.as_bytes().get(value_range.start).map_or(false, |c| matcher.is_separator(*c)) {
+            if !matcher.eq_separator() {
+                return Err(self.without_value(idx, key));
+            }
+
+            value_range.start += 1;
+        } else if !matcher.short_space_opt() {
+            // Key must be followed by `=` if not `short_space_opt`
+            return Err(self.without_value(idx, key));
+        }
+
+        // Check for quoted value.
+        if let Some(c) = value.as_bytes().get(value_range.start).cloned() {
+            if c == b'"' || c == b'\'' {
+                value_range.start += 1;
+
+                // A closing quote must be the same as an opening one.
+                if ends_with(&value[value_range.start..], c) {
+                    value_range.end -= 1;
+                } else {
+                    return Err(self.without_value(idx, key));
+                }
+            }
+        }
+
+        // Check length, otherwise String::drain will panic.
+        if value_range.end - value_range.start == 0 {
+            return Err(self.without_value(idx, key));
+        }
+
+        // Extract `value` from `--key="value"`.
+        let value = &value[value_range];
+
+        if value.is_empty() {
+            return Err(self.without_value(idx, key));
+        }
+
+        Ok(value)
+    }
+
+    // Parses a `-xvf value` or `-xvfvalue` flags cluster, where `-f` is the key.
+    // Only arguments before `end` are searched.
+    #[inline(never)]
+    fn find_cluster_value(
+        &self,
+        keys: &Keys,
+        end: usize,
+    ) -> Result<Option<(&str, PairKind, usize)>, Error> {
+        if !self.matcher().combined_flags() {
+            return Ok(None);
+        }
+
+        for (idx, item) in self.options().iter().enumerate().take(end) {
+            if let Some(s) = item.to_str() {
+                if !s.starts_with('-') || s.starts_with("--") {
+                    continue;
+                }
+
+                if let Some(pos) = self.find_short_flag(&s[1..], keys) {
+                    let pos = pos + 1;
+                    let key = &s[pos..pos + 1];
+                    let mut value = &s[pos + 1..];
+                    if value.starts_with('=') {
+                        // Like with `short_space_opt`, `-K=value` is ambiguous
+                        // without `eq_separator`.
+                        if self.matcher().eq_separator() {
+                            value = &value[1..];
+                        } else {
+                            continue;
+                        }
+                    }
+
+                    if !value.is_empty() {
+                        return Ok(Some((value, PairKind::Cluster(pos, false), idx)));
+                    }
+
+                    if idx + 1 == self.args.len() {
+                        return Err(self.without_value(idx, &format!("-{}", key)));
+                    }
+
+                    let value = self.arg_str(idx + 1)?;
+
+                    return Ok(Some((value, PairKind::Cluster(pos, true), idx)));
+                }
+            }
+        }
+
+        Ok(None)
+    }
+
+    // Returns the position of the first short key inside a flags combination.
+    #[inline(never)]
+    fn find_short_flag(&self, flags: &str, keys: &Keys) -> Option<usize> {
+        keys.short_flags().filter_map(|c| self.config.key_matching.find_byte(flags, c)).min()
+    }
+
+    /// Parses an option with an optional value using `FromStr` trait.
+    ///
+    /// This is a shorthand for `opt_flag_or_value_from_fn("--key", FromStr::from_str)`
+    pub fn opt_flag_or_value_from_str<'a, A, T>(&mut self, keys: A) -> Result<Option<Option<T>>, Error>
+    where
+        A: Into<Keys<'a>>,
+        T: FromStr,
+        <T as FromStr>::Err: Display,
+    {
+        self.opt_flag_or_value_from_fn(keys, FromStr::from_str)
+    }
+
+    /// Parses an option with an optional value using a specified function.
+    ///
+    /// Such options can be used as flags and as key-value pairs, like `ls --color`
+    /// and `ls --color=always`.
+    /// Unlike [`opt_value_from_fn`], the value must be attached to the key:
+    /// `--key=value` when [`Config::eq_separator`] is enabled
+    /// and `-Kvalue` when [`Config::short_space_opt`] is enabled.
+    /// The next argument is never treated as a value, so `--color always`
+    /// is a flag followed by a free-standing argument.
+    ///
+    /// Returns `Ok(None)` when option is not present and `Ok(Some(None))`
+    /// when option is present without a value.
+    ///
+    /// # Errors
+    ///
+    /// - When value is not a UTF-8 string.
+    /// - When value parsing failed.
+    /// - When value is empty, like `--color=`.
+    ///
+    /// [`opt_value_from_fn`]: struct.Arguments.html#method.opt_value_from_fn
+    /// [`Config::eq_separator`]: struct.Config.html#structfield.eq_separator
+    /// [`Config::short_space_opt`]: struct.Config.html#structfield.short_space_opt
+    pub fn opt_flag_or_value_from_fn<'a, A: Into<Keys<'a>>, T, E: Display>(
+        &mut self,
+        keys: A,
+        f: fn(&str) -> Result<T, E>,
+    ) -> Result<Option<Option<T>>, Error> {
+        self.opt_flag_or_value_from_fn_impl(&keys.into(), f)
+    }
+
+    #[inline(never)]
+    fn opt_flag_or_value_from_fn_impl<T, E: Display>(
+        &mut self,
+        keys: &Keys,
+        f: fn(&str) -> Result<T, E>,
+    ) -> Result<Option<Option<T>>, Error> {
+        self.remember(keys);
+        match self.find_key(keys) {
+            Some((idx, key, true)) => {
+                let value = self.attached_value(idx, key)?;
+                match f(value) {
+                    Ok(value) => {
+                        // Remove only when all checks are passed.
+                        self.remove_arg(idx);
+                        Ok(Some(Some(value)))
+                    }
+                    Err(e) => Err(self.parsing_failed(idx, value, error_to_string(e))),
+                }
+            }
+            Some((idx, _, false)) => {
+                self.remove_arg(idx);
+                Ok(Some(None))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Parses multiple key-value pairs into the `Vec` using `FromStr` trait.
+    ///
+    /// This is a shorthand for `values_from_fn("--key", FromStr::from_str)`
+    pub fn values_from_str<'a, A, T>(&mut self, keys: A) -> Result<Vec<T>, Error>
+        where
+            A: Into<Keys<'a>>,
+            T: FromStr,
+            <T as FromStr>::Err: Display,
+    {
+        self.values_from_fn(keys, FromStr::from_str)
+    }
+
+    /// Parses multiple key-value pairs into the `Vec` using a specified function.
+    ///
+    /// This functions can be used to parse arguments like:<br>
+    /// `--file /path1 --file /path2 --file /path3`<br>
+    /// But not `--file /path1 /path2 /path3`. Use [`multi_values_from_fn`] for this.
+    ///
+    /// Arguments can also be separated: `--file /path1 --some-flag --file /path2`
+    ///
+    /// This method simply executes [`opt_value_from_fn`] multiple times.
+    ///
+    /// An empty `Vec` is not an error.
+    ///
+    /// [`opt_value_from_fn`]: struct.Arguments.html#method.opt_value_from_fn
+    /// [`multi_values_from_fn`]: struct.Arguments.html#method.multi_values_from_fn
+    pub fn values_from_fn<'a, A: Into<Keys<'a>>, T, E: Display>(
+        &mut self,
+        keys: A,
+        f: fn(&str) -> Result<T, E>,
+    ) -> Result<Vec<T>, Error> {
+        let keys = keys.into();
+        self.remember(&keys);
+
+        let mut values = Vec::new();
+        loop {
+            match self.next_value_from_fn(&keys, f) {
+                Ok(Some(v)) => values.push(v),
+                Ok(None) => break,
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok(values)
+    }
+
+    /// Parses delimiter-separated values into the `Vec` using `FromStr` trait.
+    ///
+    /// This is a shorthand for `delimited_values_from_fn("--key", delimiter, FromStr::from_str)`
+    pub fn delimited_values_from_str<'a, A, T>(
+        &mut self,
+        keys: A,
+        delimiter: char,
+    ) -> Result<Vec<T>, Error>
+        where
+            A: Into<Keys<'a>>,
+            T: FromStr,
+            <T as FromStr>::Err: Display,
+    {
+        self.delimited_values_from_fn(keys, delimiter, FromStr::from_str)
+    }
+
+    /// Parses delimiter-separated values into the `Vec` using a specified function.
     ///
-    /// This is a shorthand for `values_from_fn("--key", FromStr::from_str)`
-    pub fn values_from_str<A, T>(&mut self, keys: A) -> Result<Vec<T>, Error>
+    /// This functions can be used to parse arguments like:<br>
+    /// `--features serde,tokio --features log`
+    ///
+    /// Each value is split using `delimiter` and values from all occurrences
+    /// of the option are collected.
+    /// The delimiter can be escaped with `\`, while `\\` is a backslash itself.
+    /// Empty items are passed to the parsing function as is,
+    /// except an empty value, which has no items at all.
+    ///
+    /// An empty `Vec` is not an error.
+    ///
+    /// # Errors
+    ///
+    /// - When value is not a UTF-8 string.
+    /// - When item parsing failed. The error contains the item and its position.
+    pub fn delimited_values_from_fn<'a, A: Into<Keys<'a>>, T, E: Display>(
+        &mut self,
+        keys: A,
+        delimiter: char,
+        f: fn(&str) -> Result<T, E>,
+    ) -> Result<Vec<T>, Error> {
+        self.delimited_values_from_fn_impl(&keys.into(), delimiter, f)
+    }
+
+    #[inline(never)]
+    fn delimited_values_from_fn_impl<T, E: Display>(
+        &mut self,
+        keys: &Keys,
+        delimiter: char,
+        f: fn(&str) -> Result<T, E>,
+    ) -> Result<Vec<T>, Error> {
+        self.remember(keys);
+        let mut values = Vec::new();
+        while let Some((value, kind, idx)) = self.find_any_value(keys)? {
+            for (i, item) in split_escaped(value, delimiter).into_iter().enumerate() {
+                match f(&item) {
+                    Ok(value) => values.push(value),
+                    Err(e) => {
+                        let (index, raw) = self.origin(value_index(idx, kind));
+                        return Err(Error::ListItemParsingFailed {
+                            value: item,
+                            position: i + 1,
+                            cause: error_to_string(e),
+                            index,
+                            raw,
+                        });
+                    }
+                }
+            }
+
+            // Remove only when all checks are passed.
+            self.remove_pair(idx, kind);
+        }
+
+        Ok(values)
+    }
+
+    /// Parses `name=value` properties using `FromStr` trait.
+    ///
+    /// This is a shorthand for
+    /// `properties_from_fn("--key", policy, FromStr::from_str, FromStr::from_str)`
+    pub fn properties_from_str<'a, A, K, V>(
+        &mut self,
+        keys: A,
+        policy: DuplicatePolicy,
+    ) -> Result<Vec<(K, V)>, Error>
+        where
+            A: Into<Keys<'a>>,
+            K: FromStr + PartialEq,
+            <K as FromStr>::Err: Display,
+            V: FromStr,
+            <V as FromStr>::Err: Display,
+    {
+        self.properties_from_fn(keys, policy, FromStr::from_str, FromStr::from_str)
+    }
+
+    /// Parses `name=value` properties using specified functions.
+    ///
+    /// This functions can be used to parse Java/CMake-style definitions like:<br>
+    /// `-D foo=1 --define bar=2`<br>
+    /// Or `-Dfoo=1` when [`Config::short_space_opt`] is enabled.
+    ///
+    /// The value of each option is split at the first `=`.
+    /// Names are parsed using `fk` and values are parsed using `fv`.
+    ///
+    /// Properties are returned in the order of their first appearance.
+    /// `policy` controls what happens when a name is set more than once.
+    ///
+    /// An empty `Vec` is not an error.
+    ///
+    /// # Errors
+    ///
+    /// - When value is not a UTF-8 string.
+    /// - When value doesn't contain `=`.
+    /// - When name or value parsing failed.
+    /// - When a name is set more than once and `policy` is `DuplicatePolicy::Error`.
+    ///
+    /// [`Config::short_space_opt`]: struct.Config.html#structfield.short_space_opt
+    pub fn properties_from_fn<'a, A: Into<Keys<'a>>, K: PartialEq, V, E1: Display, E2: Display>(
+        &mut self,
+        keys: A,
+        policy: DuplicatePolicy,
+        fk: fn(&str) -> Result<K, E1>,
+        fv: fn(&str) -> Result<V, E2>,
+    ) -> Result<Vec<(K, V)>, Error> {
+        let keys = keys.into();
+        self.remember(&keys);
+        let mut properties: Vec<(K, V)> = Vec::new();
+        while let Some((value, kind, idx)) = self.find_any_value(&keys)? {
+            let index = value_index(idx, kind);
+            let (raw_name, value) = match split_property(value) {
+                Some(v) => v,
+                None => {
+                    let cause = "expected NAME=VALUE".to_string();
+                    return Err(self.parsing_failed(index, value, cause));
+                }
+            };
+
+            let name = fk(raw_name)
+                .map_err(|e| self.parsing_failed(index, raw_name, error_to_string(e)))?;
+
+            let value = fv(value)
+                .map_err(|e| self.parsing_failed(index, value, error_to_string(e)))?;
+
+            if let Some(pos) = properties.iter().position(|p| p.0 == name) {
+                match policy {
+                    DuplicatePolicy::Error => {
+                        let (index, raw) = self.origin(index);
+                        return Err(Error::DuplicateProperty {
+                            keys: keys.to_static(),
+                            name: raw_name.to_string(),
+                            index,
+                            raw,
+                        });
+                    }
+                    DuplicatePolicy::FirstWins => {}
+                    DuplicatePolicy::LastWins => properties[pos].1 = value,
+                }
+            } else {
+                properties.push((name, value));
+            }
+
+            // Remove only when all checks are passed.
+            self.remove_pair(idx, kind);
+        }
+
+        Ok(properties)
+    }
+
+    /// Parses an option with a fixed number of values.
+    ///
+    /// This functions can be used to parse arguments like:<br>
+    /// `--resize 800 600` or `--map SRC DST`<br>
+    /// into a tuple or an array. See [`FromValues`] for supported types.
+    ///
+    /// The values must be separated from each other by spaces.
+    /// The first value can be attached to the key, like `--point=1.5 2.0`,
+    /// when the `=` separator is enabled via [`Config`].
+    /// Like with [`value_from_fn`], the arguments after the key are treated as values,
+    /// even if they have a `-/--` prefix. So `--point -1.5 2.0` is not an error.
+    ///
+    /// # Errors
+    ///
+    /// - When option is not present.
+    /// - When there are fewer values than required.
+    /// - When value is not a UTF-8 string.
+    /// - When value parsing failed.
+    ///
+    /// [`FromValues`]: trait.FromValues.html
+    /// [`value_from_fn`]: struct.Arguments.html#method.value_from_fn
+    /// [`Config`]: struct.Config.html
+    pub fn tuple_from_str<'a, A: Into<Keys<'a>>, T: FromValues>(&mut self, keys: A) -> Result<T, Error> {
+        let keys = keys.into();
+        match self.opt_tuple_from_str(&keys) {
+            Ok(Some(v)) => Ok(v),
+            Ok(None) => Err(Error::MissingOption(keys.to_static())),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Parses an optional option with a fixed number of values.
+    ///
+    /// The same as [`tuple_from_str`], but returns `Ok(None)` when option is not present.
+    ///
+    /// [`tuple_from_str`]: struct.Arguments.html#method.tuple_from_str
+    pub fn opt_tuple_from_str<'a, A: Into<Keys<'a>>, T: FromValues>(
+        &mut self,
+        keys: A,
+    ) -> Result<Option<T>, Error> {
+        let keys = keys.into();
+        self.remember(&keys);
+        let (idx, end, values) = match self.find_fixed_values(&keys, T::COUNT)? {
+            Some(v) => v,
+            None => return Ok(None),
+        };
+
+        let value = match T::from_values(&values) {
+            Ok(v) => v,
+            Err((i, cause)) => {
+                // The first value can be attached to the key.
+                let start = end - values.len();
+                return Err(self.parsing_failed(start + i, values[i], cause));
+            }
+        };
+
+        // Remove only when all checks are passed.
+        self.drain_args(idx, end);
+        Ok(Some(value))
+    }
+
+    // Returns the range of a key and its values, along with the values themselves.
+    #[inline(never)]
+    fn find_fixed_values(
+        &self,
+        keys: &Keys,
+        count: usize,
+    ) -> Result<Option<MultiValues<'_>>, Error> {
+        let (idx, key, attached) = match self.find_key(keys) {
+            Some(v) => v,
+            None => return Ok(None),
+        };
+
+        let mut values = Vec::with_capacity(count);
+        if attached {
+            values.push(self.attached_value(idx, key)?);
+        }
+
+        let needed = count.saturating_sub(values.len());
+        let found = std::cmp::min(self.args.len() - idx - 1, needed);
+        if found < needed {
+            let missing = needed - found;
+            let (index, raw) = self.origin(idx);
+            let key = key.to_string();
+            return Err(Error::MissingValues { key, expected: count, missing, index, raw });
+        }
+
+        let end = idx + 1 + needed;
+        for i in idx + 1..end {
+            values.push(self.arg_str(i)?);
+        }
+
+        Ok(Some((idx, end, values)))
+    }
+
+    /// Parses options with multiple values into the `Vec` using `FromStr` trait.
+    ///
+    /// This is a shorthand for `multi_values_from_fn("--key", arity, FromStr::from_str)`
+    pub fn multi_values_from_str<'a, A, R, T>(&mut self, keys: A, arity: R) -> Result<Vec<T>, Error>
         where
-            A: Into<Keys>,
+            A: Into<Keys<'a>>,
+            R: RangeBounds<usize>,
             T: FromStr,
             <T as FromStr>::Err: Display,
     {
-        self.values_from_fn(keys, FromStr::from_str)
+        self.multi_values_from_fn(keys, arity, FromStr::from_str)
     }
 
-    /// Parses multiple key-value pairs into the `Vec` using a specified function.
+    /// Parses options with multiple values into the `Vec` using a specified function.
     ///
     /// This functions can be used to parse arguments like:<br>
-    /// `--file /path1 --file /path2 --file /path3`<br>
-    /// But not `--file /path1 /path2 /path3`.
+    /// `--file /path1 /path2 /path3`
     ///
-    /// Arguments can also be separated: `--file /path1 --some-flag --file /path2`
+    /// All arguments after the key are treated as values until the next argument
+    /// that starts with `-` (except `-` itself), `--` or the end of input.
+    /// So negative numbers cannot be used as values.
     ///
-    /// This method simply executes [`opt_value_from_fn`] multiple times.
+    /// `arity` sets the number of values each occurrence of the option must have,
+    /// like `1..`, `1..=3` or `..`. Arguments beyond the maximum are not consumed
+    /// and can be parsed as free-standing arguments.
+    ///
+    /// Values from all occurrences of the option are collected:
+    /// `--file /path1 /path2 --some-flag --file /path3`
+    ///
+    /// The first value can be attached to the key, like `--file=/path1 /path2`,
+    /// when the `=` separator is enabled via [`Config`].
     ///
     /// An empty `Vec` is not an error.
     ///
-    /// [`opt_value_from_fn`]: struct.Arguments.html#method.opt_value_from_fn
-    pub fn values_from_fn<A: Into<Keys>, T, E: Display>(
+    /// # Errors
+    ///
+    /// - When an option has fewer values than `arity` allows.
+    /// - When value is not a UTF-8 string.
+    /// - When value parsing failed.
+    ///
+    /// [`Config`]: struct.Config.html
+    pub fn multi_values_from_fn<'a, A: Into<Keys<'a>>, R: RangeBounds<usize>, T, E: Display>(
         &mut self,
         keys: A,
+        arity: R,
         f: fn(&str) -> Result<T, E>,
     ) -> Result<Vec<T>, Error> {
-        let keys = keys.into();
+        let min = match arity.start_bound() {
+            Bound::Included(&n) => n,
+            Bound::Excluded(&n) => n + 1,
+            Bound::Unbounded => 0,
+        };
+
+        let max = match arity.end_bound() {
+            Bound::Included(&n) => Some(n),
+            Bound::Excluded(&n) => Some(n.saturating_sub(1)),
+            Bound::Unbounded => None,
+        };
+
+        self.multi_values_from_fn_impl(&keys.into(), min, max, f)
+    }
 
+    #[inline(never)]
+    fn multi_values_from_fn_impl<T, E: Display>(
+        &mut self,
+        keys: &Keys,
+        min: usize,
+        max: Option<usize>,
+        f: fn(&str) -> Result<T, E>,
+    ) -> Result<Vec<T>, Error> {
+        self.remember(keys);
         let mut values = Vec::new();
-        loop {
-            match self.opt_value_from_fn(keys, f) {
-                Ok(Some(v)) => values.push(v),
-                Ok(None) => break,
-                Err(e) => return Err(e),
+        while let Some((idx, end, found)) = self.find_multi_values(keys, min, max)? {
+            // The first value can be attached to the key.
+            let start = end - found.len();
+            for (i, value) in found.into_iter().enumerate() {
+                match f(value) {
+                    Ok(value) => values.push(value),
+                    Err(e) => return Err(self.parsing_failed(start + i, value, error_to_string(e))),
+                }
             }
+
+            // Remove only when all checks are passed.
+            self.drain_args(idx, end);
         }
 
         Ok(values)
     }
 
+    // Returns the range of a key and its values, along with the values themselves.
+    #[inline(never)]
+    fn find_multi_values(
+        &self,
+        keys: &Keys,
+        min: usize,
+        max: Option<usize>,
+    ) -> Result<Option<MultiValues<'_>>, Error> {
+        let (idx, key, attached) = match self.find_key(keys) {
+            Some(v) => v,
+            None => return Ok(None),
+        };
+
+        let mut values = Vec::new();
+        if attached {
+            values.push(self.attached_value(idx, key)?);
+        }
+
+        let mut end = idx + 1;
+        while end < self.args.len() && !self.matcher().is_option_like(&self.args[end]) {
+            if max.map_or(false, |max| values.len() >= max) {
+                break;
+            }
+
+            values.push(self.arg_str(end)?);
+            end += 1;
+        }
+
+        let found = values.len();
+        if found < min {
+            let (index, raw) = self.origin(idx);
+            return Err(Error::TooFewValues { key: key.to_string(), min, found, index, raw });
+        }
+
+        Ok(Some((idx, end, values)))
+    }
+
     /// Parses a key-value pair using a specified function.
     ///
     /// Unlike [`value_from_fn`], parses `&OsStr` and not `&str`.
     ///
-    /// Must be used only once for each option.
+    /// Must be used only once for each option, unless a policy is set
+    /// via [`Config::duplicate_policy`].
     ///
     /// # Errors
     ///
@@ -445,17 +2103,19 @@ impl Arguments {
     /// - When value parsing failed.
     /// - When key-value pair is separated not by space.
     ///   Only [`value_from_fn`] supports `=` separator.
+    /// - When option is set more than once and the policy is `DuplicatePolicy::Error`.
     ///
     /// [`value_from_fn`]: struct.Arguments.html#method.value_from_fn
-    pub fn value_from_os_str<A: Into<Keys>, T, E: Display>(
+    /// [`Config::duplicate_policy`]: struct.Config.html#structfield.duplicate_policy
+    pub fn value_from_os_str<'a, A: Into<Keys<'a>>, T, E: Display>(
         &mut self,
         keys: A,
         f: fn(&OsStr) -> Result<T, E>,
     ) -> Result<T, Error> {
         let keys = keys.into();
-        match self.opt_value_from_os_str(keys, f) {
+        match self.opt_value_from_os_str(&keys, f) {
             Ok(Some(v)) => Ok(v),
-            Ok(None) => Err(Error::MissingOption(keys)),
+            Ok(None) => Err(Error::MissingOption(keys.to_static())),
             Err(e) => Err(e),
         }
     }
@@ -465,80 +2125,298 @@ impl Arguments {
     /// The same as [`value_from_os_str`], but returns `Ok(None)` when option is not present.
     ///
     /// [`value_from_os_str`]: struct.Arguments.html#method.value_from_os_str
-    pub fn opt_value_from_os_str<A: Into<Keys>, T, E: Display>(
+    pub fn opt_value_from_os_str<'a, A: Into<Keys<'a>>, T, E: Display>(
         &mut self,
         keys: A,
         f: fn(&OsStr) -> Result<T, E>,
     ) -> Result<Option<T>, Error> {
-        self.opt_value_from_os_str_impl(keys.into(), f)
+        self.opt_value_from_os_str_impl(&keys.into(), f)
     }
 
     #[inline(never)]
     fn opt_value_from_os_str_impl<T, E: Display>(
         &mut self,
-        keys: Keys,
+        keys: &Keys,
+        f: fn(&OsStr) -> Result<T, E>,
+    ) -> Result<Option<T>, Error> {
+        self.remember(keys);
+        if let Some(policy) = self.config.duplicate_policy {
+            return match self.select_by_policy(keys, policy, true)? {
+                Some((item, pairs)) => match f(&item.value) {
+                    Ok(value) => {
+                        // Remove only when all checks are passed.
+                        self.remove_pairs(pairs);
+                        Ok(Some(value))
+                    }
+                    Err(e) => Err(Error::ArgumentParsingFailed {
+                        cause: error_to_string(e),
+                        index: item.index,
+                        raw: item.raw,
+                    }),
+                },
+                None => Ok(None),
+            };
+        }
+
+        self.next_value_from_os_str(keys, f)
+    }
+
+    fn next_value_from_os_str<T, E: Display>(
+        &mut self,
+        keys: &Keys,
         f: fn(&OsStr) -> Result<T, E>,
     ) -> Result<Option<T>, Error> {
         if let Some((idx, key)) = self.index_of(keys) {
             // Parse a `--key value` pair.
 
-            let value = match self.0.get(idx + 1) {
+            let value = match self.args.get(idx + 1) {
                 Some(v) => v,
-                None => return Err(Error::OptionWithoutAValue(key)),
+                None => return Err(self.without_value(idx, key)),
+            };
+
+            match f(value) {
+                Ok(value) => {
+                    // Remove only when all checks are passed.
+                    self.remove_arg(idx);
+                    self.remove_arg(idx);
+                    Ok(Some(value))
+                }
+                Err(e) => {
+                    let (index, raw) = self.origin(idx + 1);
+                    Err(Error::ArgumentParsingFailed { cause: error_to_string(e), index, raw })
+                }
+            }
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Parses multiple key-value pairs into the `Vec` using a specified function.
+    ///
+    /// This method simply executes [`opt_value_from_os_str`] multiple times.
+    ///
+    /// Unlike [`values_from_fn`], parses `&OsStr` and not `&str`.
+    ///
+    /// An empty `Vec` is not an error.
+    ///
+    /// [`opt_value_from_os_str`]: struct.Arguments.html#method.opt_value_from_os_str
+    /// [`values_from_fn`]: struct.Arguments.html#method.values_from_fn
+    pub fn values_from_os_str<'a, A: Into<Keys<'a>>, T, E: Display>(
+        &mut self,
+        keys: A,
+        f: fn(&OsStr) -> Result<T, E>,
+    ) -> Result<Vec<T>, Error> {
+        let keys = keys.into();
+        self.remember(&keys);
+        let mut values = Vec::new();
+        loop {
+            match self.next_value_from_os_str(&keys, f) {
+                Ok(Some(v)) => values.push(v),
+                Ok(None) => break,
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok(values)
+    }
+
+    // Finds all occurrences of an option and selects one according to the policy.
+    //
+    // Occurrences are searched on a copy, so nothing is removed until the value is parsed.
+    // When `os` is set, only `--key value` pairs are allowed, like in `value_from_os_str`.
+    #[inline(never)]
+    fn select_by_policy(
+        &self,
+        keys: &Keys,
+        policy: DuplicatePolicy,
+        os: bool,
+    ) -> Result<Option<(Occurrence, Pairs)>, Error> {
+        let mut scan = self.clone();
+        let mut found = Vec::new();
+        let mut pairs = Vec::new();
+        loop {
+            let (value, kind, idx) = if os {
+                match scan.index_of(keys) {
+                    Some((idx, key)) => match scan.args.get(idx + 1) {
+                        Some(value) => (value.clone(), PairKind::TwoArguments, idx),
+                        None => return Err(scan.without_value(idx, key)),
+                    },
+                    None => break,
+                }
+            } else {
+                match scan.find_any_value(keys)? {
+                    Some((value, kind, idx)) => (OsString::from(value), kind, idx),
+                    None => break,
+                }
             };
 
-            match f(value) {
-                Ok(value) => {
-                    // Remove only when all checks are passed.
-                    self.0.remove(idx);
-                    self.0.remove(idx);
-                    Ok(Some(value))
+            // Original positions don't change, so they can be used to find the pair here.
+            let key = scan.origin(idx);
+            if let Some(pos) = self.indices.iter().position(|i| *i == key.0) {
+                pairs.push((pos, kind));
+            }
+
+            let (index, raw) = scan.origin(value_index(idx, kind));
+            found.push(Occurrence { key, value, index, raw });
+            scan.remove_pair(idx, kind);
+        }
+
+        if policy == DuplicatePolicy::Error && found.len() > 1 {
+            let second = found.swap_remove(1);
+            let (first, first_raw) = found.swap_remove(0).key;
+            let (index, raw) = second.key;
+            let keys = keys.to_static();
+            return Err(Error::DuplicateOption { keys, first, first_raw, index, raw });
+        }
+
+        let item = if policy == DuplicatePolicy::LastWins {
+            found.pop()
+        } else {
+            found.into_iter().next()
+        };
+
+        Ok(item.map(|item| (item, pairs)))
+    }
+
+    // Removes pairs found by `select_by_policy`, starting from the last one,
+    // so the positions of the rest stay valid.
+    #[inline(never)]
+    fn remove_pairs(&mut self, pairs: Pairs) {
+        for (idx, kind) in pairs.into_iter().rev() {
+            self.remove_pair(idx, kind);
+        }
+    }
+
+    // Remembers queried keys, so they can be suggested for unknown options.
+    #[inline(never)]
+    fn remember(&mut self, keys: &Keys) {
+        for key in &keys.0 {
+            if !key.is_empty() && !self.queried.iter().any(|k| k == key) {
+                self.queried.push(key.to_string());
+            }
+        }
+    }
+
+    // Removes an argument along with its original position.
+    #[inline(never)]
+    fn remove_arg(&mut self, idx: usize) -> OsString {
+        self.indices.remove(idx);
+        self.args.remove(idx)
+    }
+
+    #[inline(never)]
+    fn drain_args(&mut self, start: usize, end: usize) {
+        self.indices.drain(start..end);
+        self.args.drain(start..end);
+    }
+
+    // Returns the original position of an argument and the argument as it was passed,
+    // even if it was modified since, like a flags combination or an expanded abbreviation.
+    #[inline(never)]
+    fn origin(&self, idx: usize) -> (usize, OsString) {
+        let index = self.indices[idx];
+        (index, self.argv[index].clone())
+    }
+
+    #[inline(never)]
+    fn arg_str(&self, idx: usize) -> Result<&str, Error> {
+        match self.args[idx].to_str() {
+            Some(s) => Ok(s),
+            None => {
+                let (index, raw) = self.origin(idx);
+                Err(Error::NonUtf8Argument { index, raw })
+            }
+        }
+    }
+
+    #[inline(never)]
+    fn parsing_failed(&self, idx: usize, value: &str, cause: String) -> Error {
+        let (index, raw) = self.origin(idx);
+        Error::Utf8ArgumentParsingFailed { value: value.to_string(), cause, index, raw }
+    }
+
+    #[inline(never)]
+    fn without_value(&self, idx: usize, key: &str) -> Error {
+        let (index, raw) = self.origin(idx);
+        Error::OptionWithoutAValue { key: key.to_string(), index, raw }
+    }
+
+    // Returns arguments that can be matched as flags and options.
+    #[inline(never)]
+    fn options(&self) -> &[OsString] {
+        if self.config.posix_mode {
+            &self.args[..self.first_free_index()]
+        } else {
+            &self.args
+        }
+    }
+
+    // Returns the index of the first argument that doesn't look like a flag or an option.
+    #[inline(never)]
+    fn first_free_index(&self) -> usize {
+        let mut idx = 0;
+        while idx < self.args.len() {
+            match self.global_len(idx) {
+                0 => {
+                    if !self.matcher().is_option_like(&self.args[idx]) {
+                        return idx;
+                    }
+
+                    idx += 1;
+                }
+                n => idx += n,
+            }
+        }
+
+        self.args.len()
+    }
+
+    // Returns the number of arguments occupied by a global flag or option at `idx`.
+    // Zero means that the argument is not a global one.
+    #[inline(never)]
+    fn global_len(&self, idx: usize) -> usize {
+        let arg = &self.args[idx];
+        for &(ref keys, has_value) in &self.globals {
+            for key in &keys.0 {
+                if key.is_empty() {
+                    continue;
+                }
+
+                if self.matcher().matches(arg, key) {
+                    return if has_value { 2 } else { 1 };
                 }
-                Err(e) => {
-                    Err(Error::ArgumentParsingFailed { cause: error_to_string(e) })
+
+                if has_value && index_predicate(arg, key, self.matcher()) {
+                    return 1;
                 }
             }
-        } else {
-            Ok(None)
         }
+
+        0
     }
 
-    /// Parses multiple key-value pairs into the `Vec` using a specified function.
-    ///
-    /// This method simply executes [`opt_value_from_os_str`] multiple times.
-    ///
-    /// Unlike [`values_from_fn`], parses `&OsStr` and not `&str`.
-    ///
-    /// An empty `Vec` is not an error.
-    ///
-    /// [`opt_value_from_os_str`]: struct.Arguments.html#method.opt_value_from_os_str
-    /// [`values_from_fn`]: struct.Arguments.html#method.values_from_fn
-    pub fn values_from_os_str<A: Into<Keys>, T, E: Display>(
-        &mut self,
-        keys: A,
-        f: fn(&OsStr) -> Result<T, E>,
-    ) -> Result<Vec<T>, Error> {
-        let keys = keys.into();
-        let mut values = Vec::new();
-        loop {
-            match self.opt_value_from_os_str(keys, f) {
-                Ok(Some(v)) => values.push(v),
-                Ok(None) => break,
-                Err(e) => return Err(e),
-            }
+    // Keys are validated on use, since their format depends on the syntax.
+    #[inline]
+    fn validate_keys(&self, keys: &Keys) {
+        if self.config.syntax == Syntax::Dos && keys.0.iter().any(|key| key.starts_with('/')) {
+            return;
         }
 
-        Ok(values)
+        for key in &keys.0 {
+            debug_assert!(key.starts_with('-'), "an argument should start with '-'");
+            if self.config.syntax != Syntax::Go && !key.starts_with("--") {
+                validate_shortflag(key);
+            }
+        }
     }
 
+    // Returns the first argument that matches any of the keys.
     #[inline(never)]
-    fn index_of(&self, keys: Keys) -> Option<(usize, &'static str)> {
-        // Do not unroll loop to save space, because it creates a bigger file.
-        // Which is strange, since `index_of2` actually benefits from it.
-
-        for key in &keys.0 {
-            if !key.is_empty() {
-                if let Some(i) = self.0.iter().position(|v| v == key) {
+    fn index_of<'k>(&self, keys: &'k Keys) -> Option<(usize, &'k str)> {
+        self.validate_keys(keys);
+        for (i, v) in self.options().iter().enumerate() {
+            for key in &keys.0 {
+                if !key.is_empty() && self.matcher().matches(v, key) {
                     return Some((i, key));
                 }
             }
@@ -547,20 +2425,13 @@ impl Arguments {
         None
     }
 
-    #[cfg(any(feature = "eq-separator", feature = "short-space-opt"))]
     #[inline(never)]
-    fn index_of2(&self, keys: Keys) -> Option<(usize, &'static str)> {
-        // Loop unroll to save space.
-
-        if !keys.first().is_empty() {
-            if let Some(i) = self.0.iter().position(|v| index_predicate(v, keys.first())) {
-                return Some((i, keys.first()));
-            }
-        }
-
-        if !keys.second().is_empty() {
-            if let Some(i) = self.0.iter().position(|v| index_predicate(v, keys.second())) {
-                return Some((i, keys.second()));
+    fn index_of2<'k>(&self, keys: &'k Keys) -> Option<(usize, &'k str)> {
+        for (i, v) in self.options().iter().enumerate() {
+            for key in &keys.0 {
+                if !key.is_empty() && index_predicate(v, key, self.matcher()) {
+                    return Some((i, key));
+                }
             }
         }
 
@@ -641,18 +2512,24 @@ impl Arguments {
         &mut self,
         f: fn(&str) -> Result<T, E>,
     ) -> Result<Option<T>, Error> {
-        if self.0.is_empty() {
-            Ok(None)
-        } else {
-            let value = self.0.remove(0);
-            let value = os_to_str(value.as_os_str())?;
-            match f(&value) {
+        if let Some((index, arg)) = self.next_free() {
+            let raw = self.argv[index].clone();
+            let value = match arg.to_str() {
+                Some(v) => v,
+                None => return Err(Error::NonUtf8Argument { index, raw }),
+            };
+
+            match f(value) {
                 Ok(value) => Ok(Some(value)),
                 Err(e) => Err(Error::Utf8ArgumentParsingFailed {
                     value: value.to_string(),
                     cause: error_to_string(e),
+                    index,
+                    raw,
                 }),
             }
+        } else {
+            Ok(None)
         }
     }
 
@@ -666,24 +2543,116 @@ impl Arguments {
         &mut self,
         f: fn(&OsStr) -> Result<T, E>,
     ) -> Result<Option<T>, Error> {
-        if self.0.is_empty() {
-            Ok(None)
-        } else {
-            let value = self.0.remove(0);
-            match f(value.as_os_str()) {
+        if let Some((index, arg)) = self.next_free() {
+            match f(&arg) {
                 Ok(value) => Ok(Some(value)),
-                Err(e) => Err(Error::ArgumentParsingFailed { cause: error_to_string(e) }),
+                Err(e) => {
+                    let raw = self.argv[index].clone();
+                    Err(Error::ArgumentParsingFailed { cause: error_to_string(e), index, raw })
+                }
+            }
+        } else {
+            Ok(None)
+        }
+    }
+
+    // Free-standing arguments are taken from the front, continuing past `--`.
+    #[inline(never)]
+    fn next_free(&mut self) -> Option<(usize, OsString)> {
+        if !self.args.is_empty() {
+            let index = self.indices[0];
+            return Some((index, self.remove_arg(0)));
+        }
+
+        match self.trailing {
+            Some(ref mut trailing) if !trailing.is_empty() => {
+                self.trailing_index += 1;
+                Some((self.trailing_index - 1, trailing.remove(0)))
             }
+            _ => None,
         }
     }
 
+    /// Returns arguments that follow the `--` terminator.
+    ///
+    /// They are removed from the parser, so neither free-standing argument parsing
+    /// nor [`finish`] will see them afterwards.
+    ///
+    /// Returns an empty `Vec` when there were no `--`.
+    ///
+    /// [`finish`]: struct.Arguments.html#method.finish
+    pub fn take_trailing(&mut self) -> Vec<OsString> {
+        self.trailing.take().unwrap_or_default()
+    }
+
     /// Returns a list of remaining arguments.
     ///
     /// It's up to the caller what to do with them.
     /// One can report an error about unused arguments,
     /// other can use them for further processing.
+    ///
+    /// If `--` was present and its arguments were not taken via [`take_trailing`],
+    /// they will be returned as well, preceded by the `--` itself.
+    ///
+    /// [`take_trailing`]: struct.Arguments.html#method.take_trailing
     pub fn finish(self) -> Vec<OsString> {
-        self.0
+        let mut args = self.args;
+        if let Some(trailing) = self.trailing {
+            args.push("--".into());
+            args.extend(trailing);
+        }
+
+        args
+    }
+
+    /// Checks that there are no arguments left.
+    ///
+    /// Unlike [`finish`], reports remaining arguments as an error instead of returning them.
+    /// Arguments after `--` that were not taken via [`take_trailing`]
+    /// are reported as free-standing, while the `--` itself is ignored.
+    ///
+    /// Each unknown option comes with the closest key that was queried during parsing,
+    /// including global flags and options, so `--verbos` would suggest `--verbose`.
+    ///
+    /// # Errors
+    ///
+    /// - When there are unused flags, options or free-standing arguments.
+    ///
+    /// [`finish`]: struct.Arguments.html#method.finish
+    /// [`take_trailing`]: struct.Arguments.html#method.take_trailing
+    pub fn finish_strict(self) -> Result<(), Error> {
+        let matcher = self.matcher();
+        let mut known: Vec<&str> = self.queried.iter().map(|k| k.as_str()).collect();
+        for (keys, _) in &self.globals {
+            known.extend(keys.0.iter().filter(|k| !k.is_empty()).map(|k| k.as_ref()));
+        }
+
+        let mut options = Vec::new();
+        let mut free = Vec::new();
+        for (arg, index) in self.args.iter().zip(&self.indices) {
+            if matcher.is_option_like(arg) {
+                let text = arg.to_string_lossy();
+                // Ignore the value of a `--key=value` pair.
+                let name = match text.bytes().position(|c| matcher.is_separator(c)) {
+                    Some(i) => &text[..i],
+                    None => &text,
+                };
+
+                options.push((*index, arg.clone(), closest(name, &known)));
+            } else {
+                free.push((*index, arg.clone()));
+            }
+        }
+
+        if let Some(ref trailing) = self.trailing {
+            free.extend((self.trailing_index..).zip(trailing.iter().cloned()));
+        }
+
+        if options.is_empty() && free.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::UnusedArguments { options, free })
+        }
     }
 }
 
@@ -694,28 +2663,73 @@ fn error_to_string<E: Display>(e: E) -> String {
     e.to_string()
 }
 
-#[cfg(feature = "eq-separator")]
+// Returns the closest name by edit distance, if it's close enough.
 #[inline(never)]
-fn starts_with_plus_eq(text: &OsStr, prefix: &str) -> bool {
-    if let Some(s) = text.to_str() {
-        if s.get(0..prefix.len()) == Some(prefix) {
-            if s.as_bytes().get(prefix.len()) == Some(&b'=') {
-                return true;
+fn closest(text: &str, names: &[&str]) -> Option<String> {
+    // Prefixes are not compared, since they depend on the syntax, like `/out` and `--out`.
+    // Otherwise, any `-x` would be close to any `-y` as well.
+    let text = trim_prefix(text);
+    let len = text.chars().count();
+    let max = std::cmp::max(1, len / 3);
+    names.iter()
+        .map(|name| (edit_distance(text, trim_prefix(name)), name))
+        .filter(|(distance, _)| *distance <= max && *distance < len)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, name)| name.to_string())
+}
+
+fn trim_prefix(text: &str) -> &str {
+    text.trim_start_matches(|c| c == '-' || c == '+' || c == '/')
+}
+
+// Levenshtein distance between two strings, where swapping adjacent characters
+// counts as a single edit, since this is a common typo.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut rows = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in rows.iter_mut().enumerate() {
+        row[0] = i;
+    }
+
+    rows[0] = (0..=b.len()).collect();
+
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+            let mut distance = std::cmp::min(rows[i - 1][j] + 1, rows[i][j - 1] + 1);
+            distance = std::cmp::min(distance, rows[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                distance = std::cmp::min(distance, rows[i - 2][j - 2] + 1);
             }
+
+            rows[i][j] = distance;
         }
     }
 
-    false
+    rows[a.len()][b.len()]
+}
+
+// Returns the value of a `--key=value` pair.
+#[inline(never)]
+fn eq_value<'a>(text: &'a OsStr, prefix: &str, matcher: Matcher) -> Option<&'a str> {
+    let s = text.to_str()?;
+    let len = matcher.prefix_len(s, prefix)?;
+    if s.as_bytes().get(len).map_or(false, |c| matcher.is_separator(*c)) {
+        Some(&s[len + 1..])
+    } else {
+        None
+    }
 }
 
-#[cfg(feature = "short-space-opt")]
 #[inline(never)]
-fn starts_with_short_prefix(text: &OsStr, prefix: &str) -> bool {
+fn starts_with_short_prefix(text: &OsStr, prefix: &str, matcher: Matcher) -> bool {
     if prefix.starts_with("--") {
         return false; // Only works for short keys
     }
     if let Some(s) = text.to_str() {
-        if s.get(0..prefix.len()) == Some(prefix) {
+        // `/verbose` is never `-v erbose`.
+        if !s.starts_with('/') && matcher.prefix_len(s, prefix).is_some() {
             return true;
         }
     }
@@ -723,23 +2737,12 @@ fn starts_with_short_prefix(text: &OsStr, prefix: &str) -> bool {
     false
 }
 
-#[cfg(all(feature = "eq-separator", feature = "short-space-opt"))]
-#[inline]
-fn index_predicate(text: &OsStr, prefix: &str) -> bool {
-    starts_with_plus_eq(text, prefix) || starts_with_short_prefix(text, prefix)
-}
-#[cfg(all(feature = "eq-separator", not(feature = "short-space-opt")))]
-#[inline]
-fn index_predicate(text: &OsStr, prefix: &str) -> bool {
-    starts_with_plus_eq(text, prefix)
-}
-#[cfg(all(feature = "short-space-opt", not(feature = "eq-separator")))]
 #[inline]
-fn index_predicate(text: &OsStr, prefix: &str) -> bool {
-    starts_with_short_prefix(text, prefix)
+fn index_predicate(text: &OsStr, prefix: &str, matcher: Matcher) -> bool {
+    (matcher.eq_separator() && eq_value(text, prefix, matcher).is_some())
+        || (matcher.short_space_opt() && starts_with_short_prefix(text, prefix, matcher))
 }
 
-#[cfg(any(feature = "eq-separator", feature = "short-space-opt"))]
 #[inline]
 fn ends_with(text: &str, c: u8) -> bool {
     if text.is_empty() {
@@ -749,60 +2752,384 @@ fn ends_with(text: &str, c: u8) -> bool {
     }
 }
 
+fn parse_bool(text: &str) -> Option<bool> {
+    for &(s, value) in &[
+        ("true", true), ("yes", true), ("on", true), ("1", true),
+        ("false", false), ("no", false), ("off", false), ("0", false),
+    ] {
+        if text.eq_ignore_ascii_case(s) {
+            return Some(value);
+        }
+    }
+
+    None
+}
+
+// Strips up to two leading dashes.
+#[inline]
+fn trim_dashes(text: &str) -> &str {
+    if text.starts_with("--") {
+        &text[2..]
+    } else if text.starts_with('-') {
+        &text[1..]
+    } else {
+        text
+    }
+}
+
+// Splits a `name=value` property.
+#[inline(never)]
+fn split_property(text: &str) -> Option<(&str, &str)> {
+    let idx = text.find('=')?;
+    Some((&text[..idx], &text[idx + 1..]))
+}
+
+// Splits text by a delimiter, which can be escaped with a backslash.
+#[inline(never)]
+fn split_escaped(text: &str, delimiter: char) -> Vec<String> {
+    let mut items = Vec::new();
+    if text.is_empty() {
+        return items;
+    }
+
+    let mut item = String::new();
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == delimiter {
+            items.push(std::mem::replace(&mut item, String::new()));
+        } else if c == '\\' {
+            match chars.next() {
+                Some(c) if c == delimiter || c == '\\' => item.push(c),
+                Some(c) => {
+                    item.push('\\');
+                    item.push(c);
+                }
+                None => item.push('\\'),
+            }
+        } else {
+            item.push(c);
+        }
+    }
+
+    items.push(item);
+    items
+}
+
+fn validate_shortflag(short_key: &str) {
+    let mut chars = short_key[1..].chars();
+    if let Some(first) = chars.next() {
+        debug_assert!(short_key.len() == 2 || chars.all(|c| c == first),
+            "short keys should be a single character or a repeated character");
+    }
+}
+
+// Returns the position of the argument that contains the value of a pair.
 #[inline]
-fn os_to_str(text: &OsStr) -> Result<&str, Error> {
-    text.to_str().ok_or_else(|| Error::NonUtf8Argument)
+fn value_index(idx: usize, kind: PairKind) -> usize {
+    match kind {
+        PairKind::SingleArgument | PairKind::Cluster(_, false) => idx,
+        PairKind::TwoArguments | PairKind::Cluster(_, true) => idx + 1,
+    }
+}
+
+
+/// A subcommands dispatcher.
+///
+/// Allows registering handlers for subcommands, including nested ones,
+/// and calls the one that matches [`Arguments::subcommand`].
+///
+/// ```
+/// use pico_args::{Arguments, Error, Subcommands};
+///
+/// fn add(_: &mut Arguments) -> Result<&'static str, Error> { Ok("add") }
+/// fn remove(_: &mut Arguments) -> Result<&'static str, Error> { Ok("remove") }
+/// fn build(_: &mut Arguments) -> Result<&'static str, Error> { Ok("build") }
+///
+/// let commands = Subcommands::new("app")
+///     .command("build", build)
+///     .nested(Subcommands::new("remote")
+///         .command("add", add)
+///         .command("remove", remove));
+///
+/// let mut args = Arguments::from_vec(vec!["remote".into(), "add".into()]);
+/// assert_eq!(commands.dispatch(&mut args).unwrap(), "add");
+///
+/// let mut args = Arguments::from_vec(vec!["remote".into(), "ad".into()]);
+/// assert_eq!(commands.dispatch(&mut args).unwrap_err().to_string(),
+///            "app remote: argument 1 'ad': unknown subcommand, did you mean 'add'?");
+/// ```
+///
+/// [`Arguments::subcommand`]: struct.Arguments.html#method.subcommand
+#[derive(Clone)]
+pub struct Subcommands<T, E = Error> {
+    name: &'static str,
+    commands: Vec<(&'static str, Subcommand<T, E>)>,
+}
+
+// Handlers don't implement `Debug` on older compilers, so only names are printed.
+impl<T, E> fmt::Debug for Subcommands<T, E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let names: Vec<_> = self.commands.iter().map(|(name, _)| *name).collect();
+        f.debug_struct("Subcommands")
+            .field("name", &self.name)
+            .field("commands", &names)
+            .finish()
+    }
+}
+
+#[derive(Clone)]
+enum Subcommand<T, E> {
+    Handler(fn(&mut Arguments) -> Result<T, E>),
+    Nested(Subcommands<T, E>),
+}
+
+impl<T, E: From<Error>> Subcommands<T, E> {
+    /// Creates a new dispatcher.
+    ///
+    /// `name` is used in error messages. For the top-level dispatcher,
+    /// it's usually an application name.
+    /// For a nested one, it's the name of the subcommand itself.
+    pub fn new(name: &'static str) -> Self {
+        Subcommands {
+            name,
+            commands: Vec::new(),
+        }
+    }
+
+    /// Registers a subcommand handler.
+    ///
+    /// The handler receives the remaining arguments.
+    pub fn command(mut self, name: &'static str, f: fn(&mut Arguments) -> Result<T, E>) -> Self {
+        self.commands.push((name, Subcommand::Handler(f)));
+        self
+    }
+
+    /// Registers a subcommand with its own subcommands.
+    pub fn nested(mut self, commands: Subcommands<T, E>) -> Self {
+        self.commands.push((commands.name, Subcommand::Nested(commands)));
+        self
+    }
+
+    /// Parses the subcommand and calls its handler.
+    ///
+    /// Returns whatever the handler returns.
+    ///
+    /// # Errors
+    ///
+    /// - When the subcommand is missing.
+    /// - When the subcommand is unknown.
+    /// - When the subcommand is not a UTF-8 string.
+    pub fn dispatch(&self, args: &mut Arguments) -> Result<T, E> {
+        self.dispatch_impl(args, self.name.to_string())
+    }
+
+    fn dispatch_impl(&self, args: &mut Arguments, path: String) -> Result<T, E> {
+        let names: Vec<&str> = self.commands.iter().map(|(name, _)| *name).collect();
+        let name = match args.subcommand_of(&names) {
+            Ok(Some(name)) => name,
+            Ok(None) => return Err(Error::MissingSubcommand { path }.into()),
+            Err(Error::UnknownSubcommand { name, suggestion, index, raw, .. }) => {
+                return Err(Error::UnknownSubcommand { path, name, suggestion, index, raw }.into());
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        // Always found, since `subcommand_of` accepts only registered names.
+        let idx = names.iter().position(|n| *n == name).unwrap();
+        match self.commands[idx].1 {
+            Subcommand::Handler(f) => f(args),
+            Subcommand::Nested(ref commands) => {
+                commands.dispatch_impl(args, format!("{} {}", path, name))
+            }
+        }
+    }
+}
+
+
+/// A fixed number of values.
+///
+/// Used by [`Arguments::tuple_from_str`].
+/// Implemented for tuples and arrays of 2 to 4 elements that implement `FromStr`.
+///
+/// [`Arguments::tuple_from_str`]: struct.Arguments.html#method.tuple_from_str
+pub trait FromValues: Sized {
+    /// The number of values.
+    const COUNT: usize;
+
+    /// Parses values.
+    ///
+    /// `values` always contains exactly `COUNT` elements.
+    ///
+    /// On failure, returns the position of the invalid value in `values`,
+    /// which must be less than `COUNT`, and the cause.
+    fn from_values(values: &[&str]) -> Result<Self, (usize, String)>;
+}
+
+fn parse_value<T>(values: &[&str], index: usize) -> Result<T, (usize, String)>
+where
+    T: FromStr,
+    <T as FromStr>::Err: Display,
+{
+    T::from_str(values[index]).map_err(|e| (index, error_to_string(e)))
+}
+
+macro_rules! impl_from_values_for_tuple {
+    ($count:expr; $($t:ident $idx:tt),+) => {
+        impl<$($t),+> FromValues for ($($t,)+)
+        where
+            $($t: FromStr, <$t as FromStr>::Err: Display),+
+        {
+            const COUNT: usize = $count;
+
+            fn from_values(values: &[&str]) -> Result<Self, (usize, String)> {
+                Ok(($(parse_value::<$t>(values, $idx)?,)+))
+            }
+        }
+    };
+}
+
+impl_from_values_for_tuple!(2; A 0, B 1);
+impl_from_values_for_tuple!(3; A 0, B 1, C 2);
+impl_from_values_for_tuple!(4; A 0, B 1, C 2, D 3);
+
+macro_rules! impl_from_values_for_array {
+    ($count:expr; $($idx:tt),+) => {
+        impl<T> FromValues for [T; $count]
+        where
+            T: FromStr,
+            <T as FromStr>::Err: Display,
+        {
+            const COUNT: usize = $count;
+
+            fn from_values(values: &[&str]) -> Result<Self, (usize, String)> {
+                Ok([$(parse_value(values, $idx)?),+])
+            }
+        }
+    };
 }
 
+impl_from_values_for_array!(2; 0, 1);
+impl_from_values_for_array!(3; 0, 1, 2);
+impl_from_values_for_array!(4; 0, 1, 2, 3);
+
 
 /// A keys container.
 ///
 /// Should not be used directly.
 #[doc(hidden)]
-#[derive(Clone, Copy, Debug)]
-pub struct Keys([&'static str; 2]);
+#[derive(Clone, Debug)]
+pub struct Keys<'a>(Vec<Cow<'a, str>>);
 
-impl Keys {
+impl<'a> Keys<'a> {
+    // Returns single character short keys.
     #[inline]
-    fn first(&self) -> &'static str {
-        self.0[0]
+    fn short_flags<'k>(&'k self) -> impl Iterator<Item = u8> + 'k {
+        self.0.iter()
+            .filter(|key| key.len() == 2 && key.starts_with('-') && !key.starts_with("--"))
+            .map(|key| key.as_bytes()[1])
     }
 
+    #[inline(never)]
+    fn to_static(&self) -> Keys<'static> {
+        Keys(self.0.iter().map(|key| Cow::Owned(key.to_string())).collect())
+    }
+}
+
+impl<'a> Display for Keys<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, key) in self.0.iter().filter(|key| !key.is_empty()).enumerate() {
+            if i != 0 {
+                write!(f, "/")?;
+            }
+
+            write!(f, "{}", key)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> From<&'a str> for Keys<'a> {
     #[inline]
-    fn second(&self) -> &'static str {
-        self.0[1]
+    fn from(v: &'a str) -> Self {
+        Keys(vec![Cow::Borrowed(v)])
     }
 }
 
-impl From<[&'static str; 2]> for Keys {
+impl<'a> From<&'a String> for Keys<'a> {
     #[inline]
-    fn from(v: [&'static str; 2]) -> Self {
-        debug_assert!(v[0].starts_with("-"), "an argument should start with '-'");
-        validate_shortflag(v[0]);
-        debug_assert!(
-            !v[0].starts_with("--"),
-            "the first argument should be short"
-        );
-        debug_assert!(v[1].starts_with("--"), "the second argument should be long");
-        Keys(v)
+    fn from(v: &'a String) -> Self {
+        Keys::from(v.as_str())
     }
 }
 
-fn validate_shortflag(short_key: &'static str) {
-    let mut chars = short_key[1..].chars();
-    if let Some(first) = chars.next() {
-        debug_assert!(short_key.len() == 2 || chars.all(|c| c == first),
-            "short keys should be a single character or a repeated character");
+impl From<String> for Keys<'static> {
+    #[inline]
+    fn from(v: String) -> Self {
+        Keys(vec![Cow::Owned(v)])
     }
 }
 
-impl From<&'static str> for Keys {
+impl<'a> From<Cow<'a, str>> for Keys<'a> {
     #[inline]
-    fn from(v: &'static str) -> Self {
-        debug_assert!(v.starts_with("-"), "an argument should start with '-'");
-        if !v.starts_with("--") {
-            validate_shortflag(v);
-        }
-        Keys([v, ""])
+    fn from(v: Cow<'a, str>) -> Self {
+        Keys(vec![v])
+    }
+}
+
+impl<'a, 'b> From<&'b [&'a str]> for Keys<'a> {
+    #[inline]
+    fn from(v: &'b [&'a str]) -> Self {
+        Keys(v.iter().map(|key| Cow::Borrowed(*key)).collect())
+    }
+}
+
+impl<'a> From<Vec<&'a str>> for Keys<'a> {
+    #[inline]
+    fn from(v: Vec<&'a str>) -> Self {
+        Keys::from(v.as_slice())
+    }
+}
+
+impl From<Vec<String>> for Keys<'static> {
+    #[inline]
+    fn from(v: Vec<String>) -> Self {
+        Keys(v.into_iter().map(Cow::Owned).collect())
+    }
+}
+
+impl<'a> From<Vec<Cow<'a, str>>> for Keys<'a> {
+    #[inline]
+    fn from(v: Vec<Cow<'a, str>>) -> Self {
+        Keys(v)
+    }
+}
+
+impl<'a, 'b> From<&'b Keys<'a>> for Keys<'b> {
+    #[inline]
+    fn from(v: &'b Keys<'a>) -> Self {
+        Keys(v.0.iter().map(|key| Cow::Borrowed(key.as_ref())).collect())
     }
 }
+
+macro_rules! impl_keys_from_array {
+    ($($n:expr),+) => {
+        $(
+            impl<'a> From<[&'a str; $n]> for Keys<'a> {
+                #[inline]
+                fn from(v: [&'a str; $n]) -> Self {
+                    Keys::from(&v[..])
+                }
+            }
+
+            impl From<[String; $n]> for Keys<'static> {
+                #[inline]
+                fn from(v: [String; $n]) -> Self {
+                    Keys(v.iter().map(|key| Cow::Owned(key.clone())).collect())
+                }
+            }
+        )+
+    };
+}
+
+impl_keys_from_array!(1, 2, 3, 4, 5, 6, 7, 8);
@@ -133,7 +133,7 @@ fn eq_option_err_01() {
     let mut args = Arguments::from_vec(to_vec(&["-w="]));
     let value: Result<Option<u32>, Error> = args.opt_value_from_str("-w");
     assert_eq!(value.unwrap_err().to_string(),
-               "the '-w' option doesn't have an associated value");
+               "argument 0 '-w=': the '-w' option doesn't have an associated value");
 }
 
 #[cfg(feature = "eq-separator")]
@@ -142,7 +142,7 @@ fn eq_option_err_02() {
     let mut args = Arguments::from_vec(to_vec(&["-w='"]));
     let value: Result<Option<u32>, Error> = args.opt_value_from_str("-w");
     assert_eq!(value.unwrap_err().to_string(),
-               "the '-w' option doesn't have an associated value");
+               "argument 0 '-w='': the '-w' option doesn't have an associated value");
 }
 
 #[cfg(feature = "eq-separator")]
@@ -151,7 +151,7 @@ fn eq_option_err_03() {
     let mut args = Arguments::from_vec(to_vec(&["-w=''"]));
     let value: Result<Option<u32>, Error> = args.opt_value_from_str("-w");
     assert_eq!(value.unwrap_err().to_string(),
-               "the '-w' option doesn't have an associated value");
+               "argument 0 '-w=''': the '-w' option doesn't have an associated value");
 }
 
 #[cfg(feature = "eq-separator")]
@@ -160,7 +160,7 @@ fn eq_option_err_04() {
     let mut args = Arguments::from_vec(to_vec(&["-w='\""]));
     let value: Result<Option<u32>, Error> = args.opt_value_from_str("-w");
     assert_eq!(value.unwrap_err().to_string(),
-               "the '-w' option doesn't have an associated value");
+               "argument 0 '-w='\"': the '-w' option doesn't have an associated value");
 }
 
 #[cfg(feature = "eq-separator")]
@@ -169,7 +169,7 @@ fn eq_option_err_05() {
     let mut args = Arguments::from_vec(to_vec(&["-w='10\""]));
     let value: Result<Option<u32>, Error> = args.opt_value_from_str("-w");
     assert_eq!(value.unwrap_err().to_string(),
-               "the '-w' option doesn't have an associated value");
+               "argument 0 '-w='10\"': the '-w' option doesn't have an associated value");
 }
 
 #[cfg(all(feature = "eq-separator", not(feature = "short-space-opt")))]
@@ -186,7 +186,7 @@ fn eq_option_err_07() {
     let mut args = Arguments::from_vec(to_vec(&["-w=a"]));
     let value: Result<Option<u32>, Error> = args.opt_value_from_str("-w");
     assert_eq!(value.unwrap_err().to_string(),
-               "failed to parse 'a': invalid digit found in string");
+               "argument 0 '-w=a': failed to parse 'a': invalid digit found in string");
 }
 
 #[cfg(not(any(feature = "eq-separator", feature = "short-space-opt")))]
@@ -283,7 +283,7 @@ fn space_not_eq_option_err_01() {
     let mut args = Arguments::from_vec(to_vec(&["-w=10"]));
     let value: Result<Option<String>, Error> = args.opt_value_from_str("-w");
     assert_eq!(value.unwrap_err().to_string(),
-               "the \'-w\' option doesn\'t have an associated value");
+               "argument 0 \'-w=10\': the \'-w\' option doesn\'t have an associated value");
 }
 
 #[cfg(all(feature = "short-space-opt", not(feature = "eq-separator")))]
@@ -323,7 +323,8 @@ fn space_option_err_01() {
 fn space_option_err_02() {
     let mut args = Arguments::from_vec(to_vec(&["-w'10"]));
     let value: Result<Option<u32>, Error> = args.opt_value_from_str("-w");
-    assert_eq!(value.unwrap_err().to_string(), "the \'-w\' option doesn\'t have an associated value");
+    assert_eq!(value.unwrap_err().to_string(),
+               "argument 0 \'-w\'10\': the \'-w\' option doesn\'t have an associated value");
 }
 
 #[test]
@@ -353,7 +354,7 @@ fn missing_option_value_01() {
     let mut args = Arguments::from_vec(to_vec(&["--value"]));
     let value: Result<Option<u32>, Error> = args.opt_value_from_str("--value");
     assert_eq!(value.unwrap_err().to_string(),
-               "the '--value' option doesn't have an associated value");
+               "argument 0 '--value': the '--value' option doesn't have an associated value");
 }
 
 #[test]
@@ -420,14 +421,14 @@ fn opt_free_from_fn_02() {
 fn opt_free_from_fn_03() {
     let mut args = Arguments::from_vec(to_vec(&["-h"]));
     assert_eq!(args.opt_free_from_fn(u32::from_str).unwrap_err().to_string(),
-               "failed to parse '-h': invalid digit found in string");
+               "argument 0 '-h': failed to parse '-h': invalid digit found in string");
 }
 
 #[test]
 fn opt_free_from_fn_04() {
     let mut args = Arguments::from_vec(to_vec(&["a"]));
     assert_eq!(args.opt_free_from_fn(u32::from_str).unwrap_err().to_string(),
-               "failed to parse 'a': invalid digit found in string");
+               "argument 0 'a': failed to parse 'a': invalid digit found in string");
 }
 
 #[test]
@@ -437,6 +438,7 @@ fn opt_free_from_fn_05() {
 }
 
 #[test]
+#[allow(clippy::approx_constant)]
 fn opt_free_from_fn_06() {
     let mut args = Arguments::from_vec(to_vec(&["-3.14"]));
     assert_eq!(args.opt_free_from_fn(f32::from_str).unwrap(), Some(-3.14f32));
@@ -493,3 +495,1256 @@ fn subcommand() {
     let cmd = args.subcommand().unwrap();
     assert_eq!(cmd, None);
 }
+
+#[test]
+fn dash_dash_01() {
+    let mut args = Arguments::from_vec(to_vec(&["-v", "--", "-v"]));
+    assert!(args.contains("-v"));
+    assert!(!args.contains("-v"));
+    assert_eq!(args.take_trailing(), vec![OsString::from("-v")]);
+    assert_eq!(args.finish(), Vec::<OsString>::new());
+}
+
+#[test]
+fn dash_dash_02() {
+    let mut args = Arguments::from_vec(to_vec(&["--", "--width", "10"]));
+    let value: Option<u32> = args.opt_value_from_str("--width").unwrap();
+    assert_eq!(value, None);
+}
+
+#[test]
+fn dash_dash_03() {
+    let mut args = Arguments::from_vec(to_vec(&["a", "--", "-b", "--"]));
+    let value: String = args.free_from_str().unwrap();
+    assert_eq!(value, "a");
+    let value: String = args.free_from_str().unwrap();
+    assert_eq!(value, "-b");
+    let value: String = args.free_from_str().unwrap();
+    assert_eq!(value, "--");
+    let value: Option<String> = args.opt_free_from_str().unwrap();
+    assert_eq!(value, None);
+}
+
+#[test]
+fn dash_dash_04() {
+    let args = Arguments::from_vec(to_vec(&["-a", "--", "-b"]));
+    assert_eq!(args.finish(), to_vec(&["-a", "--", "-b"]));
+}
+
+#[test]
+fn dash_dash_05() {
+    let mut args = Arguments::from_vec(to_vec(&["-a"]));
+    assert_eq!(args.take_trailing(), Vec::<OsString>::new());
+}
+
+#[test]
+fn posix_mode_01() {
+    let config = Config { posix_mode: true, ..Config::default() };
+    let mut args = Arguments::from_vec_with_config(to_vec(&["-v", "run", "ls", "-v"]), config);
+    assert!(args.contains("-v"));
+    assert!(!args.contains("-v"));
+    assert_eq!(args.finish(), to_vec(&["run", "ls", "-v"]));
+}
+
+#[test]
+fn posix_mode_02() {
+    let config = Config { posix_mode: true, ..Config::default() };
+    let mut args = Arguments::from_vec_with_config(to_vec(&["--width", "10", "file", "--height", "20"]), config);
+    let value: Option<u32> = args.opt_value_from_str("--width").unwrap();
+    assert_eq!(value, Some(10));
+    let value: Option<u32> = args.opt_value_from_str("--height").unwrap();
+    assert_eq!(value, None);
+    let value: String = args.free_from_str().unwrap();
+    assert_eq!(value, "file");
+    assert_eq!(args.finish(), to_vec(&["--height", "20"]));
+}
+
+#[test]
+fn posix_mode_03() {
+    let config = Config { posix_mode: true, ..Config::default() };
+    let mut args = Arguments::from_vec_with_config(to_vec(&["-", "-v"]), config);
+    assert!(!args.contains("-v"));
+}
+
+#[test]
+fn split_subcommand_01() {
+    let mut args = Arguments::from_vec(to_vec(&["--out", "a", "build", "--out", "b"]));
+    let value: String = args.value_from_str("--out").unwrap();
+    assert_eq!(value, "a");
+
+    let (name, mut sub_args) = args.split_subcommand().unwrap().unwrap();
+    assert_eq!(name, "build");
+    assert!(args.finish().is_empty());
+
+    let value: String = sub_args.value_from_str("--out").unwrap();
+    assert_eq!(value, "b");
+    assert!(sub_args.finish().is_empty());
+}
+
+#[test]
+fn split_subcommand_02() {
+    let mut args = Arguments::from_vec(to_vec(&["-v", "build", "-v", "--", "x"]));
+    let (name, mut sub_args) = args.split_subcommand().unwrap().unwrap();
+    assert_eq!(name, "build");
+    assert!(args.contains("-v"));
+    assert!(!args.contains("-v"));
+    assert!(args.take_trailing().is_empty());
+
+    assert!(sub_args.contains("-v"));
+    assert_eq!(sub_args.take_trailing(), to_vec(&["x"]));
+}
+
+#[test]
+fn split_subcommand_03() {
+    let mut args = Arguments::from_vec(to_vec(&["-v", "--", "build"]));
+    assert!(args.split_subcommand().unwrap().is_none());
+    assert_eq!(args.finish(), to_vec(&["-v", "--", "build"]));
+}
+
+fn dispatch_build(args: &mut Arguments) -> Result<String, Error> {
+    Ok(format!("build {}", args.contains("--release")))
+}
+
+fn dispatch_remote_add(args: &mut Arguments) -> Result<String, Error> {
+    Ok(format!("remote add {}", args.free_from_str::<String>()?))
+}
+
+fn dispatch_commands() -> Subcommands<String> {
+    Subcommands::new("app")
+        .command("build", dispatch_build)
+        .nested(Subcommands::new("remote")
+            .command("add", dispatch_remote_add))
+}
+
+#[test]
+fn dispatch_01() {
+    let mut args = Arguments::from_vec(to_vec(&["build", "--release"]));
+    assert_eq!(dispatch_commands().dispatch(&mut args).unwrap(), "build true");
+}
+
+#[test]
+fn dispatch_02() {
+    let mut args = Arguments::from_vec(to_vec(&["remote", "add", "origin"]));
+    assert_eq!(dispatch_commands().dispatch(&mut args).unwrap(), "remote add origin");
+}
+
+#[test]
+fn dispatch_err_01() {
+    let mut args = Arguments::from_vec(to_vec(&["remote", "ad"]));
+    assert_eq!(dispatch_commands().dispatch(&mut args).unwrap_err().to_string(),
+               "app remote: argument 1 'ad': unknown subcommand, did you mean 'add'?");
+}
+
+#[test]
+fn dispatch_err_02() {
+    let mut args = Arguments::from_vec(to_vec(&["remote"]));
+    assert_eq!(dispatch_commands().dispatch(&mut args).unwrap_err().to_string(),
+               "app remote: subcommand is missing");
+}
+
+#[test]
+fn dispatch_err_03() {
+    let mut args = Arguments::from_vec(to_vec(&["remote", "add"]));
+    assert_eq!(dispatch_commands().dispatch(&mut args).unwrap_err().to_string(),
+               "free-standing argument is missing");
+}
+
+#[test]
+fn global_options_01() {
+    let mut args = Arguments::from_vec(to_vec(&["--verbose", "--config", "a.toml", "build", "-r"]));
+    args.add_global_flag("--verbose");
+    args.add_global_option(["-c", "--config"]);
+    assert_eq!(args.subcommand().unwrap(), Some("build".to_string()));
+    assert!(args.contains("--verbose"));
+    let value: String = args.value_from_str(["-c", "--config"]).unwrap();
+    assert_eq!(value, "a.toml");
+    assert_eq!(args.finish(), to_vec(&["-r"]));
+}
+
+#[test]
+fn global_options_02() {
+    let mut args = Arguments::from_vec(to_vec(&["-c", "a.toml", "build", "--verbose", "-r"]));
+    args.add_global_flag("--verbose");
+    args.add_global_option(["-c", "--config"]);
+
+    let (name, mut sub_args) = args.split_subcommand().unwrap().unwrap();
+    assert_eq!(name, "build");
+    assert!(sub_args.contains("-r"));
+    assert!(sub_args.finish().is_empty());
+
+    assert!(args.contains("--verbose"));
+    let value: String = args.value_from_str(["-c", "--config"]).unwrap();
+    assert_eq!(value, "a.toml");
+    assert!(args.finish().is_empty());
+}
+
+#[test]
+fn global_options_03() {
+    let mut args = Arguments::from_vec(to_vec(&["--verbose", "--other", "build"]));
+    args.add_global_flag("--verbose");
+    assert_eq!(args.subcommand().unwrap(), None);
+}
+
+#[test]
+fn global_options_04() {
+    let config = Config { posix_mode: true, ..Config::default() };
+    let mut args = Arguments::from_vec_with_config(to_vec(&["run", "ls", "--verbose"]), config);
+    args.add_global_flag("--verbose");
+
+    let (name, mut sub_args) = args.split_subcommand().unwrap().unwrap();
+    assert_eq!(name, "run");
+    assert!(args.finish().is_empty());
+    assert_eq!(sub_args.free_from_str::<String>().unwrap(), "ls");
+    assert_eq!(sub_args.finish(), to_vec(&["--verbose"]));
+}
+
+#[test]
+fn global_options_05() {
+    let mut args = config_args(&["--config=a.toml", "build"], true, false, false);
+    args.add_global_option("--config");
+    assert_eq!(args.subcommand().unwrap(), Some("build".to_string()));
+}
+
+#[test]
+fn combined_flags_value_01() {
+    let mut args = config_args(&["-xvf", "archive.tar"], false, false, true);
+    let value: String = args.value_from_str("-f").unwrap();
+    assert_eq!(value, "archive.tar");
+    assert!(args.contains("-x"));
+    assert!(args.contains("-v"));
+    assert!(args.finish().is_empty());
+}
+
+#[test]
+fn combined_flags_value_02() {
+    let mut args = config_args(&["-xvfarchive.tar", "file"], false, false, true);
+    let value: String = args.value_from_str(["-f", "--file"]).unwrap();
+    assert_eq!(value, "archive.tar");
+    assert_eq!(args.finish(), to_vec(&["-xv", "file"]));
+}
+
+#[test]
+fn combined_flags_value_03() {
+    let mut args = config_args(&["-xf"], false, false, true);
+    let value: Result<String, Error> = args.value_from_str("-f");
+    assert_eq!(value.unwrap_err().to_string(),
+               "argument 0 '-xf': the '-f' option doesn't have an associated value");
+    assert_eq!(args.finish(), to_vec(&["-xf"]));
+}
+
+#[test]
+fn combined_flags_value_04() {
+    let mut args = config_args(&["--file", "-x"], false, false, true);
+    let value: Option<String> = args.opt_value_from_str("-f").unwrap();
+    assert_eq!(value, None);
+}
+
+#[test]
+fn combined_flags_value_05() {
+    let mut args = config_args(&["-xf=archive.tar"], true, false, true);
+    let value: String = args.value_from_str("-f").unwrap();
+    assert_eq!(value, "archive.tar");
+    assert_eq!(args.finish(), to_vec(&["-x"]));
+}
+
+#[test]
+fn combined_flags_value_06() {
+    let mut args = config_args(&["-xIa", "-I", "b"], false, false, true);
+    let value: Option<String> = args.opt_value_from_str("-I").unwrap();
+    assert_eq!(value, Some("a".to_string()));
+
+    let mut args = config_args(&["-xIa", "-I", "b"], false, false, true);
+    let values: Vec<String> = args.values_from_str("-I").unwrap();
+    assert_eq!(values, vec!["a", "b"]);
+    assert_eq!(args.finish(), to_vec(&["-x"]));
+}
+
+#[test]
+fn combined_flags_value_07() {
+    let mut args = config_args(&["-xfa,b", "-f", "c"], false, false, true);
+    let values: Vec<String> = args.delimited_values_from_str("-f", ',').unwrap();
+    assert_eq!(values, vec!["a", "b", "c"]);
+
+    let mut args = config_args(&["-xDk=1", "-D", "k=2"], false, false, true);
+    let values: Vec<(String, u32)> =
+        args.properties_from_str("-D", DuplicatePolicy::LastWins).unwrap();
+    assert_eq!(values, vec![("k".to_string(), 2)]);
+}
+
+#[test]
+fn count_01() {
+    let mut args = Arguments::from_vec(to_vec(&["-v", "file", "--verbose", "-v"]));
+    assert_eq!(args.count(["-v", "--verbose"]), 3);
+    assert_eq!(args.count(["-v", "--verbose"]), 0);
+    assert_eq!(args.finish(), to_vec(&["file"]));
+}
+
+#[test]
+fn count_02() {
+    let mut args = Arguments::from_vec(to_vec(&[]));
+    assert_eq!(args.count("-v"), 0);
+}
+
+#[test]
+fn count_03() {
+    let mut args = config_args(&["-vvv", "-qv", "--verbose"], false, false, true);
+    assert_eq!(args.count(["-v", "--verbose"]), 5);
+    assert_eq!(args.finish(), to_vec(&["-q"]));
+}
+
+#[test]
+fn count_max_01() {
+    let mut args = Arguments::from_vec(to_vec(&["-v", "-v"]));
+    assert_eq!(args.count_max("-v", 2).unwrap(), 2);
+}
+
+#[test]
+fn count_max_02() {
+    let mut args = Arguments::from_vec(to_vec(&["-v", "-v", "--verbose"]));
+    assert_eq!(args.count_max(["-v", "--verbose"], 2).unwrap_err().to_string(),
+               "argument 2 '--verbose': the '-v/--verbose' flag can be set at most 2 time(s)");
+}
+
+#[test]
+fn count_max_03() {
+    let mut args = config_args(&["-v", "-xvv", "-v"], false, false, true);
+    assert_eq!(args.count_max("-v", 2).unwrap_err().to_string(),
+               "argument 1 '-xvv': the '-v' flag can be set at most 2 time(s)");
+}
+
+#[test]
+fn bool_flag_01() {
+    let mut args = Arguments::from_vec(to_vec(&["--color"]));
+    assert_eq!(args.opt_bool_flag(["-c", "--color"]).unwrap(), Some(true));
+    assert_eq!(args.opt_bool_flag(["-c", "--color"]).unwrap(), None);
+}
+
+#[test]
+fn bool_flag_02() {
+    let mut args = Arguments::from_vec(to_vec(&["-c", "file", "--no-color"]));
+    assert_eq!(args.opt_bool_flag(["-c", "--color"]).unwrap(), Some(false));
+    assert_eq!(args.finish(), to_vec(&["file"]));
+}
+
+#[test]
+fn bool_flag_03() {
+    let mut args = Arguments::from_vec(to_vec(&["--no-color", "--color"]));
+    assert_eq!(args.opt_bool_flag("--color").unwrap(), Some(true));
+}
+
+#[test]
+fn bool_flag_04() {
+    let mut args = Arguments::from_vec(to_vec(&["--no-c"]));
+    assert_eq!(args.opt_bool_flag("-c").unwrap(), None);
+}
+
+#[test]
+fn bool_flag_eq_01() {
+    let mut args = config_args(&["--cache=off", "--no-cache", "--cache=YES"], true, false, false);
+    assert_eq!(args.opt_bool_flag("--cache").unwrap(), Some(true));
+    assert!(args.finish().is_empty());
+}
+
+#[test]
+fn bool_flag_eq_02() {
+    let mut args = config_args(&["--cache", "--cache=maybe"], true, false, false);
+    assert_eq!(args.opt_bool_flag("--cache").unwrap_err().to_string(),
+               "argument 1 '--cache=maybe': failed to parse 'maybe': expected true/false, yes/no, on/off or 1/0");
+    assert_eq!(args.finish(), to_vec(&["--cache", "--cache=maybe"]));
+}
+
+#[test]
+fn bool_flag_combined_01() {
+    let mut args = config_args(&["--no-color", "-xc"], false, false, true);
+    assert_eq!(args.opt_bool_flag(["-c", "--color"]).unwrap(), Some(true));
+    assert_eq!(args.finish(), to_vec(&["-x"]));
+}
+
+#[test]
+fn flag_or_value_01() {
+    let mut args = Arguments::from_vec(to_vec(&["--color", "always"]));
+    let value: Option<Option<String>> = args.opt_flag_or_value_from_str("--color").unwrap();
+    assert_eq!(value, Some(None));
+    assert_eq!(args.finish(), to_vec(&["always"]));
+}
+
+#[test]
+fn flag_or_value_02() {
+    let mut args = Arguments::from_vec(to_vec(&["file"]));
+    let value: Option<Option<String>> = args.opt_flag_or_value_from_str("--color").unwrap();
+    assert_eq!(value, None);
+}
+
+#[test]
+fn flag_or_value_eq_01() {
+    let mut args = config_args(&["--color=always"], true, false, false);
+    let value: Option<Option<String>> = args.opt_flag_or_value_from_str("--color").unwrap();
+    assert_eq!(value, Some(Some("always".to_string())));
+    assert!(args.finish().is_empty());
+}
+
+#[test]
+fn flag_or_value_eq_02() {
+    let mut args = config_args(&["--jobs=a"], true, false, false);
+    let value: Result<Option<Option<u32>>, Error> = args.opt_flag_or_value_from_str("--jobs");
+    assert_eq!(value.unwrap_err().to_string(),
+               "argument 0 '--jobs=a': failed to parse 'a': invalid digit found in string");
+    assert_eq!(args.finish(), to_vec(&["--jobs=a"]));
+}
+
+#[test]
+fn flag_or_value_eq_03() {
+    let mut args = config_args(&["--color=always", "--color"], true, false, false);
+    let value: Option<Option<String>> = args.opt_flag_or_value_from_str("--color").unwrap();
+    assert_eq!(value, Some(Some("always".to_string())));
+    let value: Option<Option<String>> = args.opt_flag_or_value_from_str("--color").unwrap();
+    assert_eq!(value, Some(None));
+    assert!(args.finish().is_empty());
+}
+
+#[test]
+fn flag_or_value_space_01() {
+    let mut args = config_args(&["-j4"], false, true, false);
+    let value: Option<Option<u32>> = args.opt_flag_or_value_from_str(["-j", "--jobs"]).unwrap();
+    assert_eq!(value, Some(Some(4)));
+}
+
+#[test]
+fn multi_values_01() {
+    let mut args = Arguments::from_vec(to_vec(&["--files", "a", "b", "-v", "--files", "c"]));
+    let value: Vec<String> = args.multi_values_from_str("--files", ..).unwrap();
+    assert_eq!(value, &["a", "b", "c"]);
+    assert_eq!(args.finish(), to_vec(&["-v"]));
+}
+
+#[test]
+fn multi_values_02() {
+    let mut args = Arguments::from_vec(to_vec(&["--nums", "1", "2", "3", "--", "4"]));
+    let value: Vec<u32> = args.multi_values_from_str("--nums", 1..=2).unwrap();
+    assert_eq!(value, &[1, 2]);
+    assert_eq!(args.finish(), to_vec(&["3", "--", "4"]));
+}
+
+#[test]
+fn multi_values_03() {
+    let mut args = Arguments::from_vec(to_vec(&["--files", "-", "-v"]));
+    let value: Vec<String> = args.multi_values_from_str("--files", 1..).unwrap();
+    assert_eq!(value, &["-"]);
+}
+
+#[test]
+fn multi_values_04() {
+    let mut args = config_args(&["--files=a", "b", "-v", "--files", "c"], true, false, false);
+    let value: Vec<String> = args.multi_values_from_str("--files", ..).unwrap();
+    assert_eq!(value, &["a", "b", "c"]);
+    assert_eq!(args.finish(), to_vec(&["-v"]));
+}
+
+#[test]
+fn multi_values_05() {
+    let mut args = config_args(&["--nums=1", "2", "3"], true, false, false);
+    let value: Vec<u32> = args.multi_values_from_str("--nums", 1..=2).unwrap();
+    assert_eq!(value, &[1, 2]);
+    assert_eq!(args.finish(), to_vec(&["3"]));
+
+    let mut args = config_args(&["--nums=a", "2"], true, false, false);
+    let value: Result<Vec<u32>, Error> = args.multi_values_from_str("--nums", ..);
+    assert_eq!(value.unwrap_err().to_string(),
+               "argument 0 '--nums=a': failed to parse 'a': invalid digit found in string");
+    assert_eq!(args.finish(), to_vec(&["--nums=a", "2"]));
+}
+
+#[test]
+fn multi_values_err_01() {
+    let mut args = Arguments::from_vec(to_vec(&["--files", "a", "--other"]));
+    let value: Result<Vec<String>, Error> = args.multi_values_from_str("--files", 2..);
+    assert_eq!(value.unwrap_err().to_string(),
+               "argument 0 '--files': the '--files' option requires at least 2 value(s), but 1 provided");
+    assert_eq!(args.finish(), to_vec(&["--files", "a", "--other"]));
+}
+
+#[test]
+fn multi_values_err_02() {
+    let mut args = Arguments::from_vec(to_vec(&["--nums", "1", "a"]));
+    let value: Result<Vec<u32>, Error> = args.multi_values_from_str("--nums", ..);
+    assert_eq!(value.unwrap_err().to_string(),
+               "argument 2 'a': failed to parse 'a': invalid digit found in string");
+    assert_eq!(args.finish(), to_vec(&["--nums", "1", "a"]));
+}
+
+#[test]
+fn delimited_values_01() {
+    let mut args = Arguments::from_vec(to_vec(&["--exclude", "a,b", "-v", "--exclude", "c"]));
+    let value: Vec<String> = args.delimited_values_from_str("--exclude", ',').unwrap();
+    assert_eq!(value, &["a", "b", "c"]);
+    assert_eq!(args.finish(), to_vec(&["-v"]));
+}
+
+#[test]
+fn delimited_values_02() {
+    let mut args = Arguments::from_vec(to_vec(&["--sep", r"a\,b,c\\,d\e,,"]));
+    let value: Vec<String> = args.delimited_values_from_str("--sep", ',').unwrap();
+    assert_eq!(value, &["a,b", r"c\", r"d\e", "", ""]);
+}
+
+#[test]
+fn delimited_values_03() {
+    let mut args = Arguments::from_vec(to_vec(&["--nums", ""]));
+    let value: Vec<u32> = args.delimited_values_from_str("--nums", ':').unwrap();
+    assert!(value.is_empty());
+}
+
+#[test]
+fn delimited_values_err_01() {
+    let mut args = Arguments::from_vec(to_vec(&["--nums", "1,2", "--nums", "3,x"]));
+    let value: Result<Vec<u32>, Error> = args.delimited_values_from_str("--nums", ',');
+    assert_eq!(value.unwrap_err().to_string(),
+               "argument 3 '3,x': failed to parse list item 2 'x': invalid digit found in string");
+}
+
+#[test]
+fn delimited_values_eq_01() {
+    let mut args = config_args(&["--features=serde,tokio"], true, false, false);
+    let value: Vec<String> = args.delimited_values_from_str("--features", ',').unwrap();
+    assert_eq!(value, &["serde", "tokio"]);
+}
+
+#[test]
+fn properties_01() {
+    let mut args = Arguments::from_vec(to_vec(&["-D", "foo=1", "-v", "--define", "bar=a=b"]));
+    let value: Vec<(String, String)> =
+        args.properties_from_str(["-D", "--define"], DuplicatePolicy::Error).unwrap();
+    assert_eq!(value, &[("foo".to_string(), "1".to_string()), ("bar".to_string(), "a=b".to_string())]);
+    assert_eq!(args.finish(), to_vec(&["-v"]));
+}
+
+#[test]
+fn properties_02() {
+    let mut args = Arguments::from_vec(to_vec(&["-D", "a=1", "-D", "b=2", "-D", "a=3"]));
+    let value: Vec<(String, u32)> = args.properties_from_str("-D", DuplicatePolicy::FirstWins).unwrap();
+    assert_eq!(value, &[("a".to_string(), 1), ("b".to_string(), 2)]);
+
+    let mut args = Arguments::from_vec(to_vec(&["-D", "a=1", "-D", "b=2", "-D", "a=3"]));
+    let value: Vec<(String, u32)> = args.properties_from_str("-D", DuplicatePolicy::LastWins).unwrap();
+    assert_eq!(value, &[("a".to_string(), 3), ("b".to_string(), 2)]);
+}
+
+#[test]
+fn properties_err_01() {
+    let mut args = Arguments::from_vec(to_vec(&["-D", "a=1", "--define", "a=2"]));
+    let value: Result<Vec<(String, u32)>, Error> =
+        args.properties_from_str(["-D", "--define"], DuplicatePolicy::Error);
+    assert_eq!(value.unwrap_err().to_string(),
+               "argument 3 'a=2': the 'a' property is set more than once via '-D/--define'");
+}
+
+#[test]
+fn properties_err_02() {
+    let mut args = Arguments::from_vec(to_vec(&["-D", "a"]));
+    let value: Result<Vec<(String, String)>, Error> =
+        args.properties_from_str("-D", DuplicatePolicy::Error);
+    assert_eq!(value.unwrap_err().to_string(), "argument 1 'a': failed to parse 'a': expected NAME=VALUE");
+}
+
+#[test]
+fn properties_space_01() {
+    let mut args = config_args(&["-Dfoo=1", "-D", "bar=2"], false, true, false);
+    let value: Vec<(String, u32)> = args.properties_from_str("-D", DuplicatePolicy::Error).unwrap();
+    assert_eq!(value, &[("foo".to_string(), 1), ("bar".to_string(), 2)]);
+}
+
+#[test]
+fn properties_03() {
+    let mut args = Arguments::from_vec(to_vec(&["--define", "a=1", "-D", "b=2"]));
+    let value: Vec<(String, u32)> =
+        args.properties_from_str(["-D", "--define"], DuplicatePolicy::Error).unwrap();
+    assert_eq!(value, &[("a".to_string(), 1), ("b".to_string(), 2)]);
+}
+
+#[test]
+fn tuple_01() {
+    let mut args = Arguments::from_vec(to_vec(&["-v", "--point", "-1.5", "2", "file"]));
+    let value: (f32, u32) = args.tuple_from_str("--point").unwrap();
+    assert_eq!(value, (-1.5, 2));
+    assert_eq!(args.finish(), to_vec(&["-v", "file"]));
+}
+
+#[test]
+fn tuple_02() {
+    let mut args = Arguments::from_vec(to_vec(&["--map", "a", "b", "c"]));
+    let value: Option<[String; 3]> = args.opt_tuple_from_str(["-m", "--map"]).unwrap();
+    assert_eq!(value, Some(["a".to_string(), "b".to_string(), "c".to_string()]));
+}
+
+#[test]
+fn tuple_03() {
+    let mut args = Arguments::from_vec(to_vec(&[]));
+    let value: Option<(u32, u32)> = args.opt_tuple_from_str("--resize").unwrap();
+    assert_eq!(value, None);
+}
+
+#[test]
+fn tuple_err_01() {
+    let mut args = Arguments::from_vec(to_vec(&["--resize", "800"]));
+    let value: Result<(u32, u32, u32), Error> = args.tuple_from_str("--resize");
+    assert_eq!(value.unwrap_err().to_string(),
+               "argument 0 '--resize': the '--resize' option requires 3 values, 2 missing");
+    assert_eq!(args.finish(), to_vec(&["--resize", "800"]));
+}
+
+#[test]
+fn tuple_err_02() {
+    let mut args = Arguments::from_vec(to_vec(&["--resize", "800", "x"]));
+    let value: Result<[u32; 2], Error> = args.tuple_from_str("--resize");
+    assert_eq!(value.unwrap_err().to_string(),
+               "argument 2 'x': failed to parse 'x': invalid digit found in string");
+    assert_eq!(args.finish(), to_vec(&["--resize", "800", "x"]));
+}
+
+#[test]
+fn tuple_04() {
+    let mut args = config_args(&["--point=1", "2", "3"], true, false, false);
+    let value: (u32, u32) = args.tuple_from_str("--point").unwrap();
+    assert_eq!(value, (1, 2));
+    assert_eq!(args.finish(), to_vec(&["3"]));
+}
+
+#[test]
+fn tuple_err_03() {
+    let mut args = config_args(&["--point=1"], true, false, false);
+    let value: Result<(u32, u32), Error> = args.tuple_from_str("--point");
+    assert_eq!(value.unwrap_err().to_string(),
+               "argument 0 '--point=1': the '--point' option requires 2 values, 1 missing");
+
+    let mut args = config_args(&["--point=x", "2"], true, false, false);
+    let value: Result<(u32, u32), Error> = args.tuple_from_str("--point");
+    assert_eq!(value.unwrap_err().to_string(),
+               "argument 0 '--point=x': failed to parse 'x': invalid digit found in string");
+    assert_eq!(args.finish(), to_vec(&["--point=x", "2"]));
+}
+
+#[test]
+fn abbreviations_01() {
+    let mut args = Arguments::from_vec(to_vec(&["--verb", "--out", "a", "--", "--verb"]));
+    args.expand_abbreviations(&["--verbose", "--version"], &["--output"]).unwrap();
+    assert!(args.contains("--verbose"));
+    let value: String = args.value_from_str("--output").unwrap();
+    assert_eq!(value, "a");
+    assert_eq!(args.finish(), to_vec(&["--", "--verb"]));
+}
+
+#[test]
+fn abbreviations_02() {
+    let mut args = Arguments::from_vec(to_vec(&["--ver", "--verbose"]));
+    let res = args.expand_abbreviations(&["--verbose", "--version"], &[]);
+    assert_eq!(res.unwrap_err().to_string(),
+               "argument 0 '--ver': the '--ver' option is ambiguous, could be: --verbose, --version");
+    assert_eq!(args.finish(), to_vec(&["--ver", "--verbose"]));
+}
+
+#[test]
+fn abbreviations_03() {
+    let mut args = Arguments::from_vec(to_vec(&["--in", "--other"]));
+    args.expand_abbreviations(&["--in", "--input"], &[]).unwrap();
+    assert_eq!(args.finish(), to_vec(&["--in", "--other"]));
+}
+
+#[test]
+fn abbreviations_04() {
+    let mut args = Arguments::from_vec(to_vec(&["--name", "--verb", "--nam", "--verb", "--verb"]));
+    args.expand_abbreviations(&["--verbose"], &["--name"]).unwrap();
+    let values: Vec<String> = args.values_from_str("--name").unwrap();
+    assert_eq!(values, vec!["--verb", "--verb"]);
+    assert!(args.contains("--verbose"));
+    assert!(args.finish().is_empty());
+}
+
+#[test]
+fn abbreviations_eq_01() {
+    let mut args = config_args(&["--wid=10"], true, false, false);
+    args.expand_abbreviations(&[], &["--width"]).unwrap();
+    let value: u32 = args.value_from_str("--width").unwrap();
+    assert_eq!(value, 10);
+}
+
+fn loose_matching() -> KeyMatching {
+    KeyMatching { ignore_ascii_case: true, underscore_as_dash: true }
+}
+
+#[test]
+fn key_matching_01() {
+    let config = Config { key_matching: loose_matching(), ..Config::default() };
+    let mut args = Arguments::from_vec_with_config(to_vec(&["--Dry-Run", "--dry_run", "--DRY_RUN"]), config);
+    assert_eq!(args.count("--dry-run"), 3);
+}
+
+#[test]
+fn key_matching_02() {
+    let config = Config { key_matching: loose_matching(), ..Config::default() };
+    let mut args = Arguments::from_vec_with_config(to_vec(&["--Out-Dir", "a", "_-x"]), config);
+    let value: String = args.value_from_str("--out-dir").unwrap();
+    assert_eq!(value, "a");
+    assert!(!args.contains("--x"));
+}
+
+#[test]
+fn key_matching_03() {
+    let mut args = Arguments::from_vec(to_vec(&["--Dry-Run"]));
+    assert!(!args.contains("--dry-run"));
+
+    let key_matching = KeyMatching { ignore_ascii_case: false, underscore_as_dash: true };
+    let config = Config { key_matching, ..Config::default() };
+    let mut args = Arguments::from_vec_with_config(to_vec(&["--Dry-Run"]), config);
+    assert!(!args.contains("--dry-run"));
+}
+
+#[test]
+fn key_matching_eq_01() {
+    let config = Config { eq_separator: true, key_matching: loose_matching(), ..Config::default() };
+    let mut args = Arguments::from_vec_with_config(to_vec(&["--Out_Dir=a"]), config);
+    let value: String = args.value_from_str("--out-dir").unwrap();
+    assert_eq!(value, "a");
+}
+
+#[test]
+fn key_matching_combined_01() {
+    let config = Config {
+        combined_flags: true,
+        key_matching: loose_matching(),
+        ..Config::default()
+    };
+    let mut args = Arguments::from_vec_with_config(to_vec(&["-aVb"]), config);
+    assert!(args.contains("-v"));
+    assert_eq!(args.finish(), to_vec(&["-ab"]));
+}
+
+fn go_args(args: &[&str]) -> Arguments {
+    let config = Config { syntax: Syntax::Go, ..Config::default() };
+    Arguments::from_vec_with_config(to_vec(args), config)
+}
+
+#[test]
+fn go_syntax_01() {
+    let mut args = go_args(&["-name", "a", "--port", "80", "-v"]);
+    let name: String = args.value_from_str("--name").unwrap();
+    assert_eq!(name, "a");
+    let port: u32 = args.value_from_str("-port").unwrap();
+    assert_eq!(port, 80);
+    assert!(args.contains("--v"));
+    assert!(args.finish().is_empty());
+}
+
+#[test]
+fn go_syntax_02() {
+    let mut args = go_args(&["-name=a", "--out=b"]);
+    let name: String = args.value_from_str("-name").unwrap();
+    assert_eq!(name, "a");
+    let out: String = args.value_from_str(["-o", "-out"]).unwrap();
+    assert_eq!(out, "b");
+}
+
+#[test]
+fn go_syntax_03() {
+    let mut args = go_args(&["-cache=false", "-debug", "true"]);
+    assert_eq!(args.opt_bool_flag("-cache").unwrap(), Some(false));
+    assert_eq!(args.opt_bool_flag("-debug").unwrap(), Some(true));
+    assert_eq!(args.finish(), to_vec(&["true"]));
+}
+
+#[test]
+fn go_syntax_04() {
+    let mut args = go_args(&["-nameless", "---name"]);
+    assert!(!args.contains("-name"));
+}
+
+#[test]
+fn go_syntax_05() {
+    let mut args = go_args(&["-vx", "-wvalue"]);
+    assert!(!args.contains("-v"));
+    let value: Option<String> = args.opt_value_from_str("-w").unwrap();
+    assert_eq!(value, None);
+}
+
+fn dos_args(args: &[&str]) -> Arguments {
+    let config = Config { syntax: Syntax::Dos, ..Config::default() };
+    Arguments::from_vec_with_config(to_vec(args), config)
+}
+
+#[test]
+fn dos_syntax_01() {
+    let mut args = dos_args(&["/?", "/out:file.txt", "/verbose", "-q"]);
+    assert!(args.contains("-?"));
+    let out: String = args.value_from_str(["-o", "--out"]).unwrap();
+    assert_eq!(out, "file.txt");
+    assert!(args.contains(["-v", "--verbose"]));
+    assert!(args.contains("-q"));
+    assert!(args.finish().is_empty());
+}
+
+#[test]
+fn dos_syntax_02() {
+    let mut args = dos_args(&["/o=a", "/level", "3", "--level", "4"]);
+    let out: String = args.value_from_str("/o").unwrap();
+    assert_eq!(out, "a");
+    let levels: Vec<u32> = args.values_from_str("--level").unwrap();
+    assert_eq!(levels, vec![3, 4]);
+}
+
+#[test]
+fn dos_syntax_03() {
+    let mut args = dos_args(&["-o", "/o"]);
+    assert!(args.contains("/o"));
+    assert_eq!(args.finish(), to_vec(&["-o"]));
+}
+
+#[test]
+fn dos_syntax_04() {
+    let mut args = dos_args(&["/cache:off", "/verbose"]);
+    assert_eq!(args.opt_bool_flag("--cache").unwrap(), Some(false));
+    assert!(!args.contains("-v"));
+}
+
+#[test]
+fn dos_syntax_05() {
+    let mut args = dos_args(&["/x", "build"]);
+    assert_eq!(args.subcommand().unwrap(), None);
+    let mut args = dos_args(&["/set", "a", "b", "/q"]);
+    let values: Vec<String> = args.multi_values_from_str("--set", 1..).unwrap();
+    assert_eq!(values, vec!["a", "b"]);
+}
+
+#[test]
+fn dos_syntax_06() {
+    let mut args = Arguments::from_vec(to_vec(&["/out:a"]));
+    assert!(!args.contains("--out"));
+    let out: Option<String> = args.opt_value_from_str("--out").unwrap();
+    assert_eq!(out, None);
+}
+
+#[test]
+fn polarity_flag_01() {
+    let mut args = Arguments::from_vec(to_vec(&["-x", "+x"]));
+    assert_eq!(args.opt_polarity_flag("-x"), Some(false));
+    assert!(args.finish().is_empty());
+}
+
+#[test]
+fn polarity_flag_02() {
+    let mut args = Arguments::from_vec(to_vec(&["+verbose", "-v", "+y"]));
+    assert_eq!(args.opt_polarity_flag(["-v", "--verbose"]), Some(true));
+    assert_eq!(args.opt_polarity_flag("-x"), None);
+    assert_eq!(args.finish(), to_vec(&["+y"]));
+}
+
+#[test]
+fn polarity_flag_03() {
+    let mut args = Arguments::from_vec(to_vec(&["+x", "run"]));
+    assert_eq!(args.subcommand().unwrap(), Some("+x".to_string()));
+
+    let config = Config { plus_flags: true, ..Config::default() };
+    let mut args = Arguments::from_vec_with_config(to_vec(&["+x", "run"]), config);
+    assert_eq!(args.subcommand().unwrap(), None);
+    assert_eq!(args.opt_polarity_flag("-x"), Some(false));
+    assert_eq!(args.subcommand().unwrap(), Some("run".to_string()));
+}
+
+#[test]
+fn polarity_flag_04() {
+    let config = Config { plus_flags: true, posix_mode: true, ..Config::default() };
+    let mut args = Arguments::from_vec_with_config(to_vec(&["+x", "run", "-x"]), config);
+    assert_eq!(args.opt_polarity_flag("-x"), Some(false));
+    assert_eq!(args.finish(), to_vec(&["run", "-x"]));
+}
+
+#[test]
+fn polarity_flag_combined_01() {
+    let mut args = config_args(&["-abc", "+bc"], false, false, true);
+    assert_eq!(args.opt_polarity_flag("-b"), Some(false));
+    assert_eq!(args.opt_polarity_flag("-a"), Some(true));
+    assert_eq!(args.finish(), to_vec(&["-c", "+c"]));
+}
+
+#[test]
+fn polarity_flag_combined_02() {
+    let mut args = config_args(&["+verbose", "+vx"], false, false, true);
+    assert_eq!(args.opt_polarity_flag(["-v", "--verbose"]), Some(false));
+    assert_eq!(args.finish(), to_vec(&["+x"]));
+
+    let mut args = config_args(&["+verbose"], false, false, true);
+    assert_eq!(args.opt_polarity_flag(["-b", "--verbose"]), Some(false));
+    assert!(args.finish().is_empty());
+}
+
+#[test]
+fn polarity_flag_x11_01() {
+    let mut args = go_args(&["-sb", "+sb"]);
+    assert_eq!(args.opt_polarity_flag("-sb"), Some(false));
+    assert!(args.finish().is_empty());
+}
+
+#[test]
+fn runtime_keys_01() {
+    let mut args = Arguments::from_vec(to_vec(&["--plugin-opt", "a", "--no-cache"]));
+    let name = format!("--{}-opt", "plugin");
+    let value: String = args.value_from_str(&name).unwrap();
+    assert_eq!(value, "a");
+    assert!(args.contains(format!("--no-{}", "cache")));
+}
+
+#[test]
+fn runtime_keys_02() {
+    let mut args = Arguments::from_vec(to_vec(&["-p", "1"]));
+    let keys = [String::from("-p"), String::from("--port")];
+    let value: u32 = args.value_from_str(keys).unwrap();
+    assert_eq!(value, 1);
+}
+
+#[test]
+fn runtime_keys_03() {
+    let mut args = Arguments::from_vec(to_vec(&[]));
+    let error = {
+        let name = String::from("--out");
+        args.value_from_str::<_, String>(name.as_str()).unwrap_err()
+    };
+    assert_eq!(error.to_string(), "the '--out' option must be set");
+}
+
+#[test]
+fn runtime_keys_04() {
+    use std::borrow::Cow;
+
+    let mut args = Arguments::from_vec(to_vec(&["--verbose", "--abc"]));
+    args.add_global_flag(String::from("--verbose"));
+    assert!(args.contains(Cow::Borrowed("--verbose")));
+    let names = [String::from("--abcd")];
+    let names: Vec<&str> = names.iter().map(|s| s.as_str()).collect();
+    args.expand_abbreviations(&names, &[]).unwrap();
+    assert!(args.contains("--abcd"));
+}
+
+#[test]
+fn aliases_01() {
+    let mut args = Arguments::from_vec(to_vec(&["--colour", "red"]));
+    let value: String = args.value_from_str(["-c", "--color", "--colour"]).unwrap();
+    assert_eq!(value, "red");
+}
+
+#[test]
+fn aliases_02() {
+    let mut args = Arguments::from_vec(to_vec(&["--old-name", "-n", "--new-name"]));
+    assert_eq!(args.count(["--new-name", "--old-name", "-n"]), 3);
+}
+
+#[test]
+fn aliases_03() {
+    let mut args = Arguments::from_vec(to_vec(&[]));
+    let error = args.value_from_str::<_, String>(["-c", "--color", "--colour"]).unwrap_err();
+    assert_eq!(error.to_string(), "the '-c/--color/--colour' option must be set");
+}
+
+#[test]
+fn aliases_04() {
+    let mut args = Arguments::from_vec(to_vec(&["--no-colour", "--color"]));
+    let keys = vec!["--colour", "--color"];
+    assert_eq!(args.opt_bool_flag(keys).unwrap(), Some(true));
+    assert!(args.finish().is_empty());
+}
+
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic]
+fn aliases_05() {
+    let mut args = Arguments::from_vec(to_vec(&[]));
+    args.contains(["-v", "--verbose", "verbose"]);
+}
+
+#[test]
+fn aliases_combined_01() {
+    let mut args = config_args(&["-xVvf"], false, false, true);
+    assert_eq!(args.count(["-v", "-V", "--verbose"]), 2);
+    assert_eq!(args.finish(), to_vec(&["-xf"]));
+}
+
+fn config_args(args: &[&str], eq_separator: bool, short_space_opt: bool, combined_flags: bool)
+    -> Arguments
+{
+    let config = Config { eq_separator, short_space_opt, combined_flags, ..Config::default() };
+    Arguments::from_vec_with_config(to_vec(args), config)
+}
+
+#[test]
+fn config_01() {
+    let mut args = config_args(&["--width=10"], true, false, false);
+    let value: u32 = args.value_from_str("--width").unwrap();
+    assert_eq!(value, 10);
+
+    let mut args = config_args(&["--width=10"], false, false, false);
+    let value: Option<u32> = args.opt_value_from_str("--width").unwrap();
+    assert_eq!(value, None);
+}
+
+#[test]
+fn config_02() {
+    let mut args = config_args(&["-w10"], false, true, false);
+    let value: u32 = args.value_from_str("-w").unwrap();
+    assert_eq!(value, 10);
+
+    let mut args = config_args(&["-w10"], false, false, false);
+    let value: Option<u32> = args.opt_value_from_str("-w").unwrap();
+    assert_eq!(value, None);
+}
+
+#[test]
+fn config_03() {
+    let mut args = config_args(&["-abc"], false, false, true);
+    assert!(args.contains("-b"));
+    assert_eq!(args.finish(), to_vec(&["-ac"]));
+
+    let mut args = config_args(&["-abc"], false, false, false);
+    assert!(!args.contains("-b"));
+}
+
+#[test]
+fn config_04() {
+    let mut args = config_args(&["run", "-abc"], false, false, true);
+    let (_, mut child) = args.split_subcommand().unwrap().unwrap();
+    assert!(child.contains("-a"));
+}
+
+#[test]
+fn config_05() {
+    assert_eq!(Config::default().eq_separator, cfg!(feature = "eq-separator"));
+    assert_eq!(Config::default().short_space_opt, cfg!(feature = "short-space-opt"));
+    assert_eq!(Config::default().combined_flags, cfg!(feature = "combined-flags"));
+}
+
+#[test]
+fn error_position_01() {
+    let mut args = Arguments::from_vec(to_vec(&["-v", "--width", "a", "--height", "b"]));
+    assert!(args.contains("-v"));
+    let value: Result<Option<u32>, Error> = args.opt_value_from_str("--height");
+    match value.unwrap_err() {
+        Error::Utf8ArgumentParsingFailed { index, raw, .. } => {
+            assert_eq!(index, 4);
+            assert_eq!(raw, "b");
+        }
+        e => panic!("unexpected error: {}", e),
+    }
+}
+
+#[test]
+fn error_position_02() {
+    let mut args = Arguments::from_vec(to_vec(&["a", "b", "--", "c"]));
+    let _: String = args.free_from_str().unwrap();
+    let _: String = args.free_from_str().unwrap();
+    let value: Result<u32, Error> = args.free_from_str();
+    assert_eq!(value.unwrap_err().to_string(),
+               "argument 3 'c': failed to parse 'c': invalid digit found in string");
+}
+
+#[test]
+fn error_position_03() {
+    let mut args = Arguments::from_vec(to_vec(&["run", "--size", "1", "x"]));
+    let (_, mut child) = args.split_subcommand().unwrap().unwrap();
+    let value: Result<(u32, u32), Error> = child.tuple_from_str("--size");
+    assert_eq!(value.unwrap_err().to_string(),
+               "argument 3 'x': failed to parse 'x': invalid digit found in string");
+}
+
+#[cfg(unix)]
+#[test]
+fn error_position_04() {
+    use std::os::unix::ffi::OsStringExt;
+
+    let mut args = to_vec(&["--name"]);
+    args.push(OsString::from_vec(vec![0xff]));
+    let mut args = Arguments::from_vec(args);
+    let value: Result<Option<String>, Error> = args.opt_value_from_str("--name");
+    assert_eq!(value.unwrap_err().to_string(), "argument 1 '\u{FFFD}': not a UTF-8 string");
+}
+
+#[test]
+fn error_position_05() {
+    let mut args = config_args(&["-xvfabc"], false, false, true);
+    assert!(args.contains("-x"));
+    let value: Result<u32, Error> = args.value_from_str("-f");
+    assert_eq!(value.unwrap_err().to_string(),
+               "argument 0 '-xvfabc': failed to parse 'abc': invalid digit found in string");
+}
+
+#[test]
+fn error_position_06() {
+    let mut args = config_args(&["--wid=abc"], true, false, false);
+    args.expand_abbreviations(&[], &["--width"]).unwrap();
+    let value: Result<u32, Error> = args.value_from_str("--width");
+    assert_eq!(value.unwrap_err().to_string(),
+               "argument 0 '--wid=abc': failed to parse 'abc': invalid digit found in string");
+}
+
+#[test]
+fn finish_strict_01() {
+    let mut args = Arguments::from_vec(to_vec(&["-h", "file"]));
+    assert!(args.contains("-h"));
+    let _: String = args.free_from_str().unwrap();
+    assert!(args.finish_strict().is_ok());
+}
+
+#[test]
+fn finish_strict_02() {
+    let mut args = Arguments::from_vec(to_vec(&["--verbose", "a", "-h", "-x", "b"]));
+    assert!(args.contains("-h"));
+    match args.finish_strict().unwrap_err() {
+        Error::UnusedArguments { options, free } => {
+            assert_eq!(options, vec![(0, "--verbose".into(), None), (3, "-x".into(), None)]);
+            assert_eq!(free, vec![(1, "a".into()), (4, "b".into())]);
+        }
+        e => panic!("unexpected error: {}", e),
+    }
+}
+
+#[test]
+fn finish_strict_03() {
+    let args = Arguments::from_vec(to_vec(&["--verbose", "a", "--", "-b"]));
+    assert_eq!(args.finish_strict().unwrap_err().to_string(),
+               "unknown options: '--verbose' (argument 0); \
+                unexpected arguments: 'a' (argument 1), '-b' (argument 3)");
+}
+
+#[test]
+fn finish_strict_04() {
+    let mut args = Arguments::from_vec(to_vec(&["a", "--"]));
+    let _: String = args.free_from_str().unwrap();
+    assert!(args.finish_strict().is_ok());
+
+    let args = Arguments::from_vec(to_vec(&["a"]));
+    assert_eq!(args.finish_strict().unwrap_err().to_string(),
+               "unexpected arguments: 'a' (argument 0)");
+}
+
+#[test]
+fn suggestions_01() {
+    let mut args = Arguments::from_vec(to_vec(&["--verbos", "--colr=auto"]));
+    assert!(!args.contains(["-v", "--verbose"]));
+    let _: Option<String> = args.opt_value_from_str("--color").unwrap();
+    assert_eq!(args.finish_strict().unwrap_err().to_string(),
+               "unknown options: '--verbos' (argument 0, did you mean '--verbose'?), \
+                '--colr=auto' (argument 1, did you mean '--color'?)");
+}
+
+#[test]
+fn suggestions_02() {
+    let mut args = Arguments::from_vec(to_vec(&["-x", "--outdr", "--zzz"]));
+    args.add_global_option("--outdir");
+    assert!(!args.contains("-h"));
+    match args.finish_strict().unwrap_err() {
+        Error::UnusedArguments { options, .. } => {
+            assert_eq!(options[0].2, None);
+            assert_eq!(options[1].2, Some("--outdir".to_string()));
+            assert_eq!(options[2].2, None);
+        }
+        e => panic!("unexpected error: {}", e),
+    }
+}
+
+#[test]
+fn suggestions_04() {
+    let mut args = dos_args(&["/verbos"]);
+    assert!(!args.contains(["-v", "--verbose"]));
+    assert_eq!(args.finish_strict().unwrap_err().to_string(),
+               "unknown options: '/verbos' (argument 0, did you mean '--verbose'?)");
+}
+
+#[test]
+fn suggestions_03() {
+    let mut args = Arguments::from_vec(to_vec(&["biuld"]));
+    let value = args.subcommand_of(&["build", "test"]);
+    assert_eq!(value.unwrap_err().to_string(),
+               "argument 0 'biuld': unknown subcommand, did you mean 'build'?");
+
+    let mut args = Arguments::from_vec(to_vec(&["test"]));
+    assert_eq!(args.subcommand_of(&["build", "test"]).unwrap(), Some("test".to_string()));
+
+    let mut args = Arguments::from_vec(to_vec(&["run"]));
+    assert_eq!(args.subcommand_of(&["build", "test"]).unwrap_err().to_string(),
+               "argument 0 'run': unknown subcommand");
+    assert_eq!(args.finish(), to_vec(&["run"]));
+}
+
+#[test]
+fn duplicate_policy_01() {
+    let config = Config { duplicate_policy: Some(DuplicatePolicy::LastWins), ..Config::default() };
+    let mut args = Arguments::from_vec_with_config(to_vec(&["-w", "1", "--width", "2", "-v"]), config);
+    let value: u32 = args.value_from_str(["-w", "--width"]).unwrap();
+    assert_eq!(value, 2);
+    assert_eq!(args.finish(), to_vec(&["-v"]));
+}
+
+#[test]
+fn duplicate_policy_02() {
+    let config = Config { duplicate_policy: Some(DuplicatePolicy::FirstWins), ..Config::default() };
+    let mut args = Arguments::from_vec_with_config(to_vec(&["--width", "1", "-v", "-w", "a"]), config);
+    let value: u32 = args.value_from_str(["-w", "--width"]).unwrap();
+    assert_eq!(value, 1);
+    assert_eq!(args.finish(), to_vec(&["-v"]));
+}
+
+#[test]
+fn duplicate_policy_03() {
+    let config = Config { duplicate_policy: Some(DuplicatePolicy::Error), ..Config::default() };
+    let mut args = Arguments::from_vec_with_config(to_vec(&["-v", "--width", "1", "-w", "2"]), config);
+    let value: Result<u32, Error> = args.value_from_str(["-w", "--width"]);
+    assert_eq!(value.unwrap_err().to_string(),
+               "argument 3 '-w': the '-w/--width' option is already set by argument 1 '--width'");
+    assert_eq!(args.finish(), to_vec(&["-v", "--width", "1", "-w", "2"]));
+}
+
+#[test]
+fn duplicate_policy_04() {
+    let config = Config { duplicate_policy: Some(DuplicatePolicy::Error), ..Config::default() };
+    let mut args = Arguments::from_vec_with_config(to_vec(&["--width", "1"]), config);
+    let value: u32 = args.value_from_str(["-w", "--width"]).unwrap();
+    assert_eq!(value, 1);
+}
+
+#[test]
+fn duplicate_policy_05() {
+    let config = Config { duplicate_policy: Some(DuplicatePolicy::LastWins), ..Config::default() };
+    let mut args = Arguments::from_vec_with_config(to_vec(&["--name", "a", "--name", "b"]), config);
+    let values: Vec<String> = args.values_from_str("--name").unwrap();
+    assert_eq!(values, vec!["a", "b"]);
+}
+
+#[test]
+fn duplicate_policy_06() {
+    let config = Config { duplicate_policy: Some(DuplicatePolicy::LastWins), ..Config::default() };
+    let mut args = Arguments::from_vec_with_config(to_vec(&["--name", "a", "--name", "b"]), config);
+    let value: Option<OsString> = args.opt_value_from_os_str("--name", |s| {
+        Ok::<_, String>(s.to_os_string())
+    }).unwrap();
+    assert_eq!(value, Some("b".into()));
+    assert!(args.finish().is_empty());
+}
+
+#[test]
+fn duplicate_policy_07() {
+    let config = Config { duplicate_policy: Some(DuplicatePolicy::LastWins), ..Config::default() };
+    let mut args = Arguments::from_vec_with_config(to_vec(&["--width", "1", "--width", "a"]), config);
+    let value: Result<u32, Error> = args.value_from_str("--width");
+    assert_eq!(value.unwrap_err().to_string(),
+               "argument 3 'a': failed to parse 'a': invalid digit found in string");
+    assert_eq!(args.finish(), to_vec(&["--width", "1", "--width", "a"]));
+}
+
+#[test]
+fn duplicate_policy_08() {
+    let config = Config {
+        short_space_opt: true,
+        combined_flags: true,
+        duplicate_policy: Some(DuplicatePolicy::LastWins),
+        ..Config::default()
+    };
+    let mut args = Arguments::from_vec_with_config(to_vec(&["-vw1", "-w", "2", "-x"]), config);
+    let value: u32 = args.value_from_str(["-w", "--width"]).unwrap();
+    assert_eq!(value, 2);
+    assert_eq!(args.finish(), to_vec(&["-v", "-x"]));
+}